@@ -89,15 +89,14 @@ fn parser_benchmarks(c: &mut Criterion) {
         })
     });
 
-    // TODO: uncomment when 4BPP support is added
-    //
-    // c.bench_function("draw indexed 4BPP", |b| {
-    //     let mut fb = Framebuffer::<Rgb888>::new();
-    //     b.iter(|| {
-    //         let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/logo-indexed-4bpp.bmp")).unwrap();
-    //         Image::new(&bmp, Point::zero()).draw(&mut fb).unwrap();
-    //     })
-    // });
+    c.bench_function("draw indexed 4BPP", |b| {
+        let mut fb = Framebuffer::<Rgb888>::new();
+        b.iter(|| {
+            let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/logo-indexed-4bpp.bmp"))
+                .unwrap();
+            Image::new(&bmp, Point::zero()).draw(&mut fb).unwrap();
+        })
+    });
 
     c.bench_function("draw indexed 8BPP", |b| {
         let mut fb = Framebuffer::<Rgb888>::new();
@@ -38,7 +38,7 @@ fn draw_bmp<C>(data: &[u8]) -> SimulatorDisplay<Rgb888>
 where
     C: PixelColor + From<C::Raw> + Into<Rgb888>,
 {
-    let bmp = Bmp::<C>::from_slice(&data).unwrap();
+    let bmp = Bmp::<C>::from_slice(data).unwrap();
 
     let mut display = SimulatorDisplay::<Rgb888>::new(bmp.size());
 
@@ -59,7 +59,7 @@ where
         + From<Rgb888>
         + From<Gray8>,
 {
-    let bmp = DynamicBmp::<C>::from_slice(&data).unwrap();
+    let bmp = DynamicBmp::<C>::from_slice(data).unwrap();
 
     let mut display = SimulatorDisplay::<Rgb888>::new(bmp.size());
 
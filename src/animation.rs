@@ -0,0 +1,190 @@
+use core::convert::TryInto;
+
+use super::embedded_graphics_core::prelude::*;
+
+use super::{
+    get_pixel::GetPixel,
+    tile_set::{Tile, TileSet},
+};
+
+/// Direction in which the frames are arranged in a sprite strip.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StripDirection {
+    /// Frames are arranged from left to right.
+    Horizontal,
+    /// Frames are arranged from top to bottom.
+    Vertical,
+}
+
+/// Frame durations of an animation.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameDurations<'a> {
+    /// All frames use the same duration in milliseconds.
+    Uniform(u32),
+
+    /// Per frame durations in milliseconds.
+    ///
+    /// Frames without a corresponding entry in the slice have a duration of `0` and are skipped
+    /// by [`BmpAnimation::frame_at`].
+    ///
+    /// [`BmpAnimation::frame_at`]: struct.BmpAnimation.html#method.frame_at
+    PerFrame(&'a [u32]),
+
+    /// Per frame durations in milliseconds, stored in a table of little endian `u32` values.
+    ///
+    /// This can be used to load the frame durations from a sidecar file, which is stored next to
+    /// the BMP image. Frames without a corresponding entry in the table have a duration of `0`
+    /// and are skipped by [`BmpAnimation::frame_at`].
+    ///
+    /// [`BmpAnimation::frame_at`]: struct.BmpAnimation.html#method.frame_at
+    Table(&'a [u8]),
+}
+
+impl FrameDurations<'_> {
+    /// Returns the duration of the given frame in milliseconds.
+    fn get(&self, index: u32) -> u32 {
+        match self {
+            Self::Uniform(duration) => *duration,
+            Self::PerFrame(durations) => durations.get(index as usize).copied().unwrap_or(0),
+            Self::Table(table) => {
+                let offset = index as usize * 4;
+
+                table
+                    .get(offset..offset + 4)
+                    .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+                    .unwrap_or(0)
+            }
+        }
+    }
+}
+
+/// Animation stored in a sprite strip.
+///
+/// `BmpAnimation` splits an image into a number of equally sized frames, which are arranged
+/// horizontally or vertically. Each frame is returned as a [`Tile`], which can be drawn like any
+/// other image.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+/// use tinybmp::{Bmp, BmpAnimation, FrameDurations, StripDirection};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+///
+/// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/colors_rgb565.bmp")).unwrap();
+///
+/// // Use the 4x2px image as an animation with 4 frames, which are displayed for 100ms each.
+/// let animation = BmpAnimation::new(
+///     bmp,
+///     StripDirection::Horizontal,
+///     4,
+///     FrameDurations::Uniform(100),
+/// );
+///
+/// // Draw the frame which is visible 250ms after the start of the animation.
+/// let frame = animation.frame_at(250).unwrap();
+/// Image::new(&frame, Point::new(10, 20)).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`Tile`]: struct.Tile.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BmpAnimation<'a, T> {
+    frames: TileSet<T>,
+    frame_count: u32,
+    durations: FrameDurations<'a>,
+}
+
+impl<'a, T> BmpAnimation<'a, T>
+where
+    T: GetPixel + Clone,
+{
+    /// Creates a new animation.
+    ///
+    /// The image is split into `frame_count` frames along the given direction. Any remaining
+    /// pixels at the end of the strip, which don't make up a complete frame, are ignored.
+    pub fn new(
+        image: T,
+        direction: StripDirection,
+        frame_count: u32,
+        durations: FrameDurations<'a>,
+    ) -> Self {
+        let size = image.size();
+
+        let frame_size = match (direction, frame_count) {
+            (_, 0) => Size::zero(),
+            (StripDirection::Horizontal, _) => Size::new(size.width / frame_count, size.height),
+            (StripDirection::Vertical, _) => Size::new(size.width, size.height / frame_count),
+        };
+
+        Self {
+            frames: TileSet::new(image, frame_size),
+            frame_count,
+            durations,
+        }
+    }
+
+    /// Returns the number of frames.
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count.min(self.frames.len())
+    }
+
+    /// Returns the duration of the given frame in milliseconds.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn frame_duration(&self, index: u32) -> Option<u32> {
+        if index < self.frame_count() {
+            Some(self.durations.get(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the total duration of the animation in milliseconds.
+    pub fn total_duration(&self) -> u32 {
+        (0..self.frame_count()).fold(0u32, |total, index| {
+            total.saturating_add(self.durations.get(index))
+        })
+    }
+
+    /// Returns the frame with the given index.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn frame(&self, index: u32) -> Option<Tile<T>> {
+        if index < self.frame_count() {
+            self.frames.tile(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the frame which is visible after the given time has elapsed.
+    ///
+    /// The animation is looped and restarts with the first frame after the total duration of the
+    /// animation has elapsed. If the total duration is `0` the first frame is returned.
+    ///
+    /// `None` is returned if the animation doesn't contain any frames.
+    pub fn frame_at(&self, elapsed_ms: u32) -> Option<Tile<T>> {
+        let total_duration = self.total_duration();
+        if total_duration == 0 {
+            return self.frame(0);
+        }
+
+        let mut remaining = elapsed_ms % total_duration;
+
+        for index in 0..self.frame_count() {
+            let duration = self.durations.get(index);
+            if remaining < duration {
+                return self.frame(index);
+            }
+            remaining -= duration;
+        }
+
+        // Only reachable if the total duration was saturated.
+        self.frame(self.frame_count().saturating_sub(1))
+    }
+}
@@ -0,0 +1,129 @@
+use super::{
+    header::BITMAP_ARRAY_HEADER_SIZE,
+    parser::{le_u32, take},
+    Limits, ParseError, RawBmp,
+};
+
+/// OS/2 bitmap array.
+///
+/// Bitmap arrays start with the `BA` signature and contain a linked list of bitmaps, which are
+/// usually versions of the same image for different display resolutions. Each bitmap can use any
+/// of the file types listed in [`FileType`].
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::prelude::*;
+/// use tinybmp::BitmapArray;
+///
+/// let array = BitmapArray::from_slice(include_bytes!("../tests/bitmap-array.bmp")).unwrap();
+///
+/// let sizes = array
+///     .bitmaps()
+///     .map(|bmp| bmp.map(|bmp| bmp.size()))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(sizes, [Size::new(4, 2), Size::new(8, 8)]);
+/// ```
+///
+/// [`FileType`]: enum.FileType.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitmapArray<'a> {
+    data: &'a [u8],
+    limits: Limits,
+}
+
+impl<'a> BitmapArray<'a> {
+    /// Creates a bitmap array object from a byte slice.
+    ///
+    /// Only the signature of the first entry is checked by this constructor. The bitmaps are
+    /// parsed when they are accessed.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_slice_with_limits(bytes, Limits::UNLIMITED)
+    }
+
+    /// Creates a bitmap array object from a byte slice and checks that the bitmaps don't exceed
+    /// the given limits.
+    ///
+    /// The limits are checked for each bitmap when it is parsed. See [`Limits`] for more
+    /// information.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        let (_, magic) = take::<2>(bytes)?;
+        if &magic != b"BA" {
+            return Err(ParseError::InvalidFileSignature);
+        }
+
+        Ok(Self {
+            data: bytes,
+            limits,
+        })
+    }
+
+    /// Returns an iterator over the bitmaps in this array.
+    pub fn bitmaps(&self) -> BitmapArrayIter<'a> {
+        BitmapArrayIter {
+            data: self.data,
+            offset: Some(0),
+            limits: self.limits,
+        }
+    }
+}
+
+/// Iterator over the bitmaps in a bitmap array.
+///
+/// The iterator stops after the first error is returned.
+///
+/// See the [`BitmapArray::bitmaps`] method documentation for more information.
+///
+/// [`BitmapArray::bitmaps`]: struct.BitmapArray.html#method.bitmaps
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitmapArrayIter<'a> {
+    data: &'a [u8],
+    offset: Option<usize>,
+    limits: Limits,
+}
+
+impl<'a> BitmapArrayIter<'a> {
+    /// Parses the entry at the given offset and returns the bitmap and the offset of the next
+    /// entry.
+    fn parse_entry(&self, offset: usize) -> Result<(RawBmp<'a>, Option<usize>), ParseError> {
+        let input = self
+            .data
+            .get(offset..)
+            .ok_or(ParseError::UnexpectedEndOfFile)?;
+
+        let (input, magic) = take::<2>(input)?;
+        if &magic != b"BA" {
+            return Err(ParseError::InvalidFileSignature);
+        }
+
+        let (input, _header_size) = le_u32(input)?;
+        let (_input, next_offset) = le_u32(input)?;
+
+        let bmp = RawBmp::from_slice_at(self.data, offset, self.limits)?;
+
+        // An offset of `0` marks the last entry. Only forward links are followed to make sure
+        // that invalid files can't cause an infinite loop.
+        let next_offset = next_offset as usize;
+        let next_offset = (next_offset >= offset + BITMAP_ARRAY_HEADER_SIZE).then_some(next_offset);
+
+        Ok((bmp, next_offset))
+    }
+}
+
+impl<'a> Iterator for BitmapArrayIter<'a> {
+    type Item = Result<RawBmp<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset.take()?;
+
+        Some(self.parse_entry(offset).map(|(bmp, next_offset)| {
+            self.offset = next_offset;
+            bmp
+        }))
+    }
+}
@@ -0,0 +1,328 @@
+use core::marker::PhantomData;
+
+use super::embedded_graphics_core::{pixelcolor::Gray8, prelude::*, primitives::Rectangle};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    Bpp, DitherColor, FloydSteinberg, GetPixel, Limits, OrderedDither, Palette, PaletteSwap,
+    ParseError, Pixels, RawBmp, Rotation, Scaled, SolidRuns, Threshold, Transformed,
+};
+
+/// A BMP-format bitmap
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bmp<'a, C> {
+    raw_bmp: RawBmp<'a>,
+    color_type: PhantomData<C>,
+    color_key: Option<ColorKey<C>>,
+}
+
+/// Color key which is used to mark pixels as transparent.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum ColorKey<C> {
+    /// Pixels with this color are transparent.
+    Color(C),
+    /// Pixels with this raw value or color table index are transparent.
+    Index(u32),
+}
+
+impl<'a, C> Bmp<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a bitmap object from a byte slice.
+    ///
+    /// The created object keeps a shared reference to the input and does not dynamically allocate
+    /// memory.
+    ///
+    /// The color type must be explicitly specified when this method is called, for example by
+    /// using the turbofish syntax. An error is returned if the bit depth of the specified color
+    /// type doesn't match the bit depth of the BMP file.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_slice(bytes)?)
+    }
+
+    /// Creates a bitmap object from a byte slice and checks that the image doesn't exceed the
+    /// given limits.
+    ///
+    /// See [`Limits`] for more information.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_slice_with_limits(bytes, limits)?)
+    }
+
+    /// Creates a bitmap object from a byte slice which contains a BMP image without a file
+    /// header.
+    ///
+    /// See [`RawBmp::from_dib_slice`] for more information about the supported data layout.
+    ///
+    /// [`RawBmp::from_dib_slice`]: struct.RawBmp.html#method.from_dib_slice
+    pub fn from_dib_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_dib_slice(bytes)?)
+    }
+
+    /// Creates a bitmap object from a byte slice which contains a BMP image without a file
+    /// header and checks that the image doesn't exceed the given limits.
+    ///
+    /// See [`RawBmp::from_dib_slice`] and [`Limits`] for more information.
+    ///
+    /// [`RawBmp::from_dib_slice`]: struct.RawBmp.html#method.from_dib_slice
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_dib_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_dib_slice_with_limits(bytes, limits)?)
+    }
+
+    /// Creates a bitmap object from a parsed raw image.
+    pub(crate) fn from_raw(raw_bmp: RawBmp<'a>) -> Result<Self, ParseError> {
+        if raw_bmp.embedded_stream().is_some() {
+            return Err(ParseError::UnsupportedEmbeddedStream);
+        }
+
+        if C::Raw::BITS_PER_PIXEL != usize::from(raw_bmp.color_bpp().bits()) {
+            if raw_bmp.color_bpp() == Bpp::Bits32 && C::Raw::BITS_PER_PIXEL == 24 {
+                // Allow 24BPP color types for 32BPP images to support RGB888 BMP files with
+                // 4 bytes per pixel.
+                // This check could be improved by using the bit masks available in BMP headers
+                // with version >= 4, but we don't currently parse this information.
+            } else if raw_bmp.color_bpp() == Bpp::Bits64 && C::Raw::BITS_PER_PIXEL == 24 {
+                // 64BPP images are tone mapped to RGB888.
+            } else if matches!(raw_bmp.color_bpp(), Bpp::Bits1 | Bpp::Bits4 | Bpp::Bits8)
+                && raw_bmp.color_table().is_some()
+            {
+                // Allow 1BPP, 4BPP and 8BPP images with color tables to be mapped to other color
+                // types.
+            } else {
+                return Err(ParseError::MismatchedBpp(raw_bmp.color_bpp().bits()));
+            }
+        }
+
+        Ok(Self {
+            raw_bmp,
+            color_type: PhantomData,
+            color_key: None,
+        })
+    }
+
+    /// Sets a color which is treated as transparent.
+    ///
+    /// Pixels with this color are skipped while the image is drawn. Only one color key can be
+    /// used at a time and this method replaces any previously set transparent color or index.
+    pub fn with_transparent_color(mut self, color: C) -> Self {
+        self.color_key = Some(ColorKey::Color(color));
+        self
+    }
+
+    /// Sets a color table index which is treated as transparent.
+    ///
+    /// Pixels which use this color table index are skipped while the image is drawn. For images
+    /// without a color table the index is compared to the raw pixel value instead. Only one color
+    /// key can be used at a time and this method replaces any previously set transparent color or
+    /// index.
+    pub fn with_transparent_index(mut self, index: u32) -> Self {
+        self.color_key = Some(ColorKey::Index(index));
+        self
+    }
+
+    /// Returns an iterator over the pixels in this image.
+    pub fn pixels<'b>(&'b self) -> Pixels<'b, 'a, C> {
+        Pixels::new(self.raw_bmp.pixels())
+    }
+
+    /// Returns a reference to the raw BMP image.
+    ///
+    /// The [`RawBmp`] instance can be used to access lower level information about the BMP file.
+    ///
+    /// [`RawBmp`]: struct.RawBmp.html
+    pub fn as_raw(&self) -> &RawBmp<'a> {
+        &self.raw_bmp
+    }
+
+    /// Returns a view of this image which uses a replacement palette.
+    ///
+    /// The palette replaces the color table, which is embedded in the BMP file, and can use a
+    /// different color type than this image. `None` is returned if the image doesn't use a color
+    /// table.
+    ///
+    /// Note that color keys set by [`with_transparent_color`] or [`with_transparent_index`] aren't
+    /// applied to the returned image.
+    ///
+    /// [`with_transparent_color`]: #method.with_transparent_color
+    /// [`with_transparent_index`]: #method.with_transparent_index
+    pub fn with_palette<P, PC>(&self, palette: P) -> Option<PaletteSwap<'a, P, PC>>
+    where
+        P: Palette<PC>,
+        PC: PixelColor,
+    {
+        self.raw_bmp.with_palette(palette)
+    }
+
+    /// Returns a view of this image which is rotated clockwise.
+    pub fn rotated(&self, rotation: Rotation) -> Transformed<Self> {
+        Transformed::new(*self).rotated(rotation)
+    }
+
+    /// Returns a view of this image which is flipped horizontally.
+    pub fn flipped_horizontal(&self) -> Transformed<Self> {
+        Transformed::new(*self).flipped_horizontal()
+    }
+
+    /// Returns a view of this image which is flipped vertically.
+    pub fn flipped_vertical(&self) -> Transformed<Self> {
+        Transformed::new(*self).flipped_vertical()
+    }
+
+    /// Returns a view of this image which is scaled by an integer factor.
+    pub fn scaled(&self, factor: u32) -> Scaled<Self> {
+        Scaled::new(*self, factor)
+    }
+
+    /// Returns a view of this image which is scaled to the given size.
+    ///
+    /// Nearest-neighbor sampling is used to scale the image.
+    pub fn scaled_to(&self, size: Size) -> Scaled<Self> {
+        Scaled::with_size(*self, size)
+    }
+
+    /// Returns a view of this image which draws runs of identical pixels with `fill_solid`.
+    ///
+    /// Runs which are at least `min_run_length` pixels long are drawn with `fill_solid` and all
+    /// other pixels with `fill_contiguous`. See [`SolidRuns`] for more information.
+    ///
+    /// [`SolidRuns`]: struct.SolidRuns.html
+    pub fn solid_runs(&self, min_run_length: u32) -> SolidRuns<Self> {
+        SolidRuns::new(*self, min_run_length)
+    }
+}
+
+impl<C> Bmp<'_, C>
+where
+    C: PixelColor + From<<C as PixelColor>::Raw>,
+{
+    /// Returns the color of the pixel at the given point.
+    ///
+    /// `None` is returned if `p` is outside the image or if the pixel is transparent.
+    pub fn pixel(&self, p: Point) -> Option<C> {
+        self.raw_bmp
+            .pixel(p)
+            .and_then(|raw| self.raw_to_keyed_color(raw))
+    }
+
+    /// Converts a raw pixel value into a color and applies the color key.
+    fn raw_to_keyed_color(&self, raw: u32) -> Option<C> {
+        if self.color_key == Some(ColorKey::Index(raw)) {
+            return None;
+        }
+
+        let color = self.raw_bmp.raw_to_color(raw)?;
+
+        if self.color_key == Some(ColorKey::Color(color)) {
+            return None;
+        }
+
+        Some(color)
+    }
+
+    /// Returns a view of this image which is converted to `BinaryColor` by using a luminance
+    /// threshold.
+    ///
+    /// See [`Threshold`] for more information.
+    ///
+    /// [`Threshold`]: struct.Threshold.html
+    pub fn threshold(&self, threshold: u8) -> Threshold<Self>
+    where
+        C: Into<Gray8>,
+    {
+        Threshold::new(*self, threshold)
+    }
+
+    /// Returns a view of this image which uses ordered dithering to reduce the color depth.
+    ///
+    /// See [`OrderedDither`] for more information.
+    ///
+    /// [`OrderedDither`]: struct.OrderedDither.html
+    pub fn ordered_dither<O>(&self) -> OrderedDither<Self, O>
+    where
+        C: Into<Gray8>,
+        O: DitherColor,
+    {
+        OrderedDither::new(*self)
+    }
+
+    /// Returns a view of this image which uses Floyd-Steinberg dithering to reduce the color
+    /// depth.
+    ///
+    /// `buffer` must contain at least one entry per pixel in a row of the image, otherwise `None`
+    /// is returned. See [`FloydSteinberg`] for more information.
+    ///
+    /// [`FloydSteinberg`]: struct.FloydSteinberg.html
+    pub fn floyd_steinberg<'b, O>(
+        &self,
+        buffer: &'b mut [i16],
+    ) -> Option<FloydSteinberg<'b, Self, O>>
+    where
+        C: Into<Gray8>,
+        O: DitherColor,
+    {
+        FloydSteinberg::new(*self, buffer)
+    }
+}
+
+impl<C> GetPixel for Bmp<'_, C>
+where
+    C: PixelColor + From<<C as PixelColor>::Raw>,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<C> {
+        Bmp::pixel(self, p)
+    }
+
+    fn with_row_major_pixels<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut dyn FnMut(Point) -> Option<C>) -> R,
+    {
+        let mut pixel = self.raw_bmp.row_major_pixels();
+
+        f(&mut |p| pixel(p).and_then(|raw| self.raw_to_keyed_color(raw)))
+    }
+}
+
+impl<C> ImageDrawable for Bmp<'_, C>
+where
+    C: PixelColor + From<<C as PixelColor>::Raw>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if self.color_key.is_some() {
+            let mut pixel = self.raw_bmp.row_major_pixels();
+
+            draw_opaque_spans(target, self.size(), |p| {
+                pixel(p).and_then(|raw| self.raw_to_keyed_color(raw))
+            })
+        } else {
+            self.as_raw().draw(target)
+        }
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
+
+impl<C> OriginDimensions for Bmp<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.raw_bmp.size()
+    }
+}
@@ -0,0 +1,14 @@
+//! CMYK color conversion.
+
+/// Converts a raw CMYK value into a raw `Rgb888` value.
+///
+/// The CMYK value uses the same layout as the `CMYK` macro in the Windows API, with the cyan
+/// channel in the most significant byte and the black channel in the least significant byte. The
+/// conversion doesn't use color management and is only an approximation of the printed colors.
+pub(crate) fn cmyk_to_rgb(raw: u32) -> u32 {
+    let [cyan, magenta, yellow, black] = raw.to_be_bytes();
+
+    let channel = |value: u8| (255 - u32::from(value)) * (255 - u32::from(black)) / 255;
+
+    channel(cyan) << 16 | channel(magenta) << 8 | channel(yellow)
+}
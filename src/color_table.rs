@@ -1,23 +1,35 @@
-use core::convert::TryInto;
+use super::embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
 
-use embedded_graphics::prelude::*;
+use super::cmyk::cmyk_to_rgb;
 
 /// Color table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ColorTable<'a> {
     data: &'a [u8],
+
+    /// Length of each entry in bytes.
+    ///
+    /// Images with a `BITMAPCOREHEADER` use 3 byte `RGBTRIPLE` entries, all other images use
+    /// 4 byte `RGBQUAD` entries.
+    entry_len: usize,
+
+    /// The entries use CMYK colors.
+    cmyk: bool,
 }
 
 impl<'a> ColorTable<'a> {
-    pub(crate) fn new(data: &'a [u8]) -> Self {
-        Self { data }
+    pub(crate) fn new(data: &'a [u8], entry_len: usize, cmyk: bool) -> Self {
+        Self {
+            data,
+            entry_len,
+            cmyk,
+        }
     }
 
     /// Returns the number of entries.
-    // Only used in tests, hence the allow
-    #[allow(unused)]
-    fn len(&self) -> usize {
-        return self.data.len() / 4;
+    pub(crate) fn len(&self) -> usize {
+        self.data.len() / self.entry_len
     }
 
     /// Returns the raw value of a color table entry.
@@ -26,14 +38,24 @@ impl<'a> ColorTable<'a> {
     pub fn get_raw<R: RawData>(&self, index: u32) -> Option<R> {
         // MSRV: Experiment with slice::as_chunks when it's stabilized
 
-        let offset = index as usize * 4;
-        let bytes = self.data.get(offset..offset + 4)?;
+        let offset = (index as usize).checked_mul(self.entry_len)?;
+        let bytes = self.data.get(offset..offset.checked_add(self.entry_len)?)?;
 
-        let raw = u32::from_le_bytes(bytes.try_into().unwrap());
+        let mut raw = [0; 4];
+        raw[..bytes.len()].copy_from_slice(bytes);
+        let raw = u32::from_le_bytes(raw);
+        let raw = if self.cmyk { cmyk_to_rgb(raw) } else { raw };
 
         Some(R::from_u32(raw))
     }
 
+    /// Returns `true` if all entries are shades of gray.
+    pub(crate) fn is_grayscale(&self) -> bool {
+        (0..self.len() as u32)
+            .filter_map(|index| self.get::<Rgb888>(index))
+            .all(|color| color.r() == color.g() && color.r() == color.b())
+    }
+
     /// Returns a color table entry.
     ///
     /// `None` is returned if `index` is out of bounds.
@@ -46,8 +68,8 @@ impl<'a> ColorTable<'a> {
 // files under `tests/`.
 #[cfg(test)]
 mod tests {
-    use crate::{Bmp, RawBmp};
-    use embedded_graphics::pixelcolor::{raw::RawU32, BinaryColor, Rgb888};
+    use super::super::embedded_graphics_core::pixelcolor::{raw::RawU32, BinaryColor, Rgb888};
+    use super::super::{Bmp, RawBmp};
 
     #[test]
     fn chessboard_8px_1bit() {
@@ -0,0 +1,72 @@
+use super::{header::CompressionMethod, huffman::HuffmanDecoder, rle::RleDecoder};
+
+/// Row decoder for compressed image data.
+///
+/// The decoders read the rows in the order in which they are stored in the image data and can
+/// continue with the next row after a row was decoded.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) enum RowDecoder {
+    /// Modified Huffman 1D decoder.
+    Huffman(HuffmanDecoder),
+    /// RLE8 or RLE4 decoder.
+    Rle(RleDecoder),
+}
+
+impl RowDecoder {
+    /// Creates a decoder for the first row in the image data.
+    ///
+    /// `None` is returned if the compression method doesn't require a decoder.
+    pub(crate) fn new(compression_method: CompressionMethod, data: &[u8]) -> Option<Self> {
+        Some(match compression_method {
+            CompressionMethod::Huffman1d => Self::Huffman(HuffmanDecoder::new(data)),
+            CompressionMethod::Rle8 | CompressionMethod::CmykRle8 => {
+                Self::Rle(RleDecoder::new(false))
+            }
+            CompressionMethod::Rle4 | CompressionMethod::CmykRle4 => {
+                Self::Rle(RleDecoder::new(true))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Returns the index of the current row in the image data.
+    pub(crate) fn row(&self) -> u32 {
+        match self {
+            Self::Huffman(decoder) => decoder.row(),
+            Self::Rle(decoder) => decoder.row(),
+        }
+    }
+
+    /// Returns the X coordinate of the next pixel.
+    pub(crate) fn x(&self) -> u32 {
+        match self {
+            Self::Huffman(decoder) => decoder.x(),
+            Self::Rle(decoder) => decoder.x(),
+        }
+    }
+
+    /// Returns the raw value of the next pixel.
+    pub(crate) fn next_pixel(&mut self, data: &[u8]) -> u32 {
+        match self {
+            Self::Huffman(decoder) => decoder.next_pixel(data),
+            Self::Rle(decoder) => decoder.next_pixel(data),
+        }
+    }
+
+    /// Skips the given number of pixels.
+    pub(crate) fn skip(&mut self, data: &[u8], count: u32) {
+        match self {
+            Self::Huffman(decoder) => decoder.skip(data, count),
+            Self::Rle(decoder) => decoder.skip(data, count),
+        }
+    }
+
+    /// Moves the decoder to the start of the next row.
+    pub(crate) fn next_row(&mut self, data: &[u8], width: u32) {
+        match self {
+            Self::Huffman(decoder) => decoder.next_row(data, width),
+            Self::Rle(decoder) => decoder.next_row(data),
+        }
+    }
+}
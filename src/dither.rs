@@ -0,0 +1,425 @@
+use core::{cell::RefCell, marker::PhantomData};
+
+use super::embedded_graphics_core::{
+    pixelcolor::{BinaryColor, Gray2, Gray4, Gray8, GrayColor},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+};
+
+/// Color type which can be used as the output of a dithered image.
+///
+/// This trait is implemented for `BinaryColor`, `Gray2`, `Gray4` and `Gray8`.
+pub trait DitherColor: PixelColor {
+    /// Number of distinct luminance levels.
+    const LEVELS: u16;
+
+    /// Creates a color from a luminance level in the range `0..LEVELS`.
+    fn from_level(level: u8) -> Self;
+}
+
+impl DitherColor for BinaryColor {
+    const LEVELS: u16 = 2;
+
+    fn from_level(level: u8) -> Self {
+        (level != 0).into()
+    }
+}
+
+impl DitherColor for Gray2 {
+    const LEVELS: u16 = 4;
+
+    fn from_level(level: u8) -> Self {
+        Gray2::new(level)
+    }
+}
+
+impl DitherColor for Gray4 {
+    const LEVELS: u16 = 16;
+
+    fn from_level(level: u8) -> Self {
+        Gray4::new(level)
+    }
+}
+
+impl DitherColor for Gray8 {
+    const LEVELS: u16 = 256;
+
+    fn from_level(level: u8) -> Self {
+        Gray8::new(level)
+    }
+}
+
+/// Returns the luminance of a color.
+fn luma<C: Into<Gray8>>(color: C) -> i32 {
+    i32::from(color.into().luma())
+}
+
+/// Quantizes a luminance value in the range `0..=255` to the nearest output level.
+fn quantize<C: DitherColor>(value: i32) -> u8 {
+    let max_level = i32::from(C::LEVELS - 1);
+
+    ((value.clamp(0, 255) * max_level + 127) / 255) as u8
+}
+
+/// Returns the luminance of an output level in the range `0..=255`.
+fn level_luma<C: DitherColor>(level: u8) -> i32 {
+    i32::from(level) * 255 / i32::from(C::LEVELS - 1)
+}
+
+/// 4x4 Bayer threshold matrix.
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10], //
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Image which uses ordered dithering to reduce the color depth.
+///
+/// Ordered dithering uses a 4x4 Bayer matrix to decide which output level is used for each pixel.
+/// It doesn't need any additional memory and the output color of each pixel only depends on the
+/// input color and the position of the pixel. Because of this it can be combined with other
+/// image adapters, like [`Transformed`] or [`Scaled`].
+///
+/// An `OrderedDither` image can be created by calling [`Bmp::ordered_dither`] or
+/// [`DynamicBmp::ordered_dither`].
+///
+/// [`Transformed`]: struct.Transformed.html
+/// [`Scaled`]: struct.Scaled.html
+/// [`Bmp::ordered_dither`]: struct.Bmp.html#method.ordered_dither
+/// [`DynamicBmp::ordered_dither`]: struct.DynamicBmp.html#method.ordered_dither
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OrderedDither<T, C> {
+    image: T,
+    color_type: PhantomData<C>,
+}
+
+impl<T, C> OrderedDither<T, C>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+    C: DitherColor,
+{
+    /// Creates a new ordered dithering adapter.
+    pub fn new(image: T) -> Self {
+        Self {
+            image,
+            color_type: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the undithered image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+impl<T, C> OriginDimensions for OrderedDither<T, C>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<T, C> GetPixel for OrderedDither<T, C>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+    C: DitherColor,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<C> {
+        let value = luma(self.image.pixel(p)?);
+
+        let threshold = i32::from(BAYER_4X4[(p.y & 3) as usize][(p.x & 3) as usize]);
+        let max_level = i32::from(C::LEVELS - 1);
+
+        // Offset the scaled value by the threshold, which is mapped to the range `0..255`,
+        // before it's rounded down to the next lower level.
+        let offset = (threshold * 2 + 1) * 255 / 32;
+        let level = ((value * max_level + offset) / 255).min(max_level);
+
+        Some(C::from_level(level as u8))
+    }
+}
+
+impl<T, C> ImageDrawable for OrderedDither<T, C>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+    C: DitherColor,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        draw_opaque_spans(target, self.size(), |p| self.pixel(p))
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
+
+/// Image which is converted to `BinaryColor` by using a luminance threshold.
+///
+/// Pixels with a luminance greater than or equal to the threshold are converted to
+/// `BinaryColor::On` and all other pixels are converted to `BinaryColor::Off`. The default `From`
+/// conversions in embedded-graphics use a fixed threshold of 50%.
+///
+/// A `Threshold` image can be created by calling [`Bmp::threshold`] or
+/// [`DynamicBmp::threshold`].
+///
+/// [`Bmp::threshold`]: struct.Bmp.html#method.threshold
+/// [`DynamicBmp::threshold`]: struct.DynamicBmp.html#method.threshold
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Threshold<T> {
+    image: T,
+    threshold: u8,
+}
+
+impl<T> Threshold<T>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+{
+    /// Creates a new threshold adapter.
+    pub fn new(image: T, threshold: u8) -> Self {
+        Self { image, threshold }
+    }
+
+    /// Returns the luminance threshold.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Returns a reference to the unconverted image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+impl<T> OriginDimensions for Threshold<T>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<T> GetPixel for Threshold<T>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+{
+    type Color = BinaryColor;
+
+    fn pixel(&self, p: Point) -> Option<BinaryColor> {
+        let value = luma(self.image.pixel(p)?);
+
+        Some((value >= i32::from(self.threshold)).into())
+    }
+}
+
+impl<T> ImageDrawable for Threshold<T>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+{
+    type Color = BinaryColor;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        draw_opaque_spans(target, self.size(), |p| self.pixel(p))
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
+
+/// Image which uses Floyd-Steinberg error diffusion to reduce the color depth.
+///
+/// Floyd-Steinberg dithering produces better results than [`OrderedDither`], but the error of
+/// each pixel is distributed to the neighboring pixels, which requires a buffer for one row of
+/// errors. The buffer must be supplied by the caller and must contain at least one entry per
+/// pixel in a row of the image.
+///
+/// Because the error diffusion requires the pixels to be processed in order, the entire image is
+/// processed every time it's drawn, even if only a sub image is drawn.
+///
+/// A `FloydSteinberg` image can be created by calling [`Bmp::floyd_steinberg`] or
+/// [`DynamicBmp::floyd_steinberg`].
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::BinaryColor, prelude::*};
+/// use tinybmp::DynamicBmp;
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::default();
+///
+/// let data = include_bytes!("../tests/colors_rgb888_24bit.bmp");
+/// let bmp = DynamicBmp::<BinaryColor>::from_slice(data).unwrap();
+///
+/// // Use a buffer with one entry per pixel in each row.
+/// let mut buffer = [0i16; 4];
+/// let dithered = bmp.floyd_steinberg::<BinaryColor>(&mut buffer).unwrap();
+///
+/// Image::new(&dithered, Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`OrderedDither`]: struct.OrderedDither.html
+/// [`Bmp::floyd_steinberg`]: struct.Bmp.html#method.floyd_steinberg
+/// [`DynamicBmp::floyd_steinberg`]: struct.DynamicBmp.html#method.floyd_steinberg
+#[derive(Debug)]
+pub struct FloydSteinberg<'b, T, C> {
+    image: T,
+    errors: RefCell<&'b mut [i16]>,
+    color_type: PhantomData<C>,
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format, C> defmt::Format for FloydSteinberg<'_, T, C> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "FloydSteinberg {{ image: {} }}", self.image)
+    }
+}
+
+impl<'b, T, C> FloydSteinberg<'b, T, C>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+    C: DitherColor,
+{
+    /// Creates a new Floyd-Steinberg dithering adapter.
+    ///
+    /// `None` is returned if the error buffer contains less entries than the image width.
+    pub fn new(image: T, buffer: &'b mut [i16]) -> Option<Self> {
+        if buffer.len() < image.size().width as usize {
+            return None;
+        }
+
+        Some(Self {
+            image,
+            errors: RefCell::new(buffer),
+            color_type: PhantomData,
+        })
+    }
+
+    /// Returns a reference to the undithered image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+impl<T, C> OriginDimensions for FloydSteinberg<'_, T, C>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<T, C> ImageDrawable for FloydSteinberg<'_, T, C>
+where
+    T: GetPixel,
+    T::Color: Into<Gray8>,
+    C: DitherColor,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let size = self.size();
+        let width = size.width as usize;
+
+        let mut errors = self.errors.borrow_mut();
+        let errors = &mut errors[0..width];
+        errors.iter_mut().for_each(|error| *error = 0);
+
+        // Errors which are diffused to the right neighbor and to the next row. `errors` contains
+        // the errors for the current row, which are replaced by the errors for the next row once
+        // they are no longer required.
+        let mut right = 0;
+        let mut below_previous = 0;
+        let mut below_current = 0;
+
+        let pixels = Rectangle::new(Point::zero(), size)
+            .points()
+            .filter_map(|p| {
+                let x = p.x as usize;
+
+                if x == 0 {
+                    right = 0;
+                    below_previous = 0;
+                    below_current = 0;
+                }
+
+                let color = self.image.pixel(p).map(|color| {
+                    let value = luma(color) + i32::from(errors[x]) + right;
+                    let level = quantize::<C>(value);
+                    let error = value.clamp(0, 255) - level_luma::<C>(level);
+
+                    right = error * 7 / 16;
+                    if x > 0 {
+                        errors[x - 1] = (below_previous + error * 3 / 16) as i16;
+                    }
+                    below_previous = below_current + error * 5 / 16;
+                    below_current = error / 16;
+
+                    C::from_level(level)
+                });
+
+                if color.is_none() {
+                    // Transparent pixels don't diffuse any error.
+                    right = 0;
+                    if x > 0 {
+                        errors[x - 1] = below_previous as i16;
+                    }
+                    below_previous = below_current;
+                    below_current = 0;
+                }
+
+                if x == width - 1 {
+                    errors[x] = below_previous as i16;
+                }
+
+                color.map(|color| Pixel(p, color))
+            });
+
+        target.draw_iter(pixels)
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
@@ -0,0 +1,207 @@
+use core::{cell::Cell, iter, marker::PhantomData};
+
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+/// Draws an image by sending all horizontal spans of opaque pixels to the target.
+///
+/// `pixel` must return `None` for transparent pixels. Each continuous span of opaque pixels in a
+/// row is drawn by a single call to `fill_contiguous`, which means that images without
+/// transparent pixels are drawn one row at a time.
+///
+/// `pixel` is called at most once for each pixel. Because the length of a span isn't known before
+/// its pixels are read, the area passed to `fill_contiguous` extends to the end of the row and the
+/// color iterator ends at the first transparent pixel.
+pub(crate) fn draw_opaque_spans<D, F>(
+    target: &mut D,
+    size: Size,
+    mut pixel: F,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    F: FnMut(Point) -> Option<D::Color>,
+{
+    let width = size.width as i32;
+
+    for y in 0..size.height as i32 {
+        let mut x = 0;
+
+        while x < width {
+            // Skip transparent pixels.
+            let first = match pixel(Point::new(x, y)) {
+                Some(color) => color,
+                None => {
+                    x += 1;
+                    continue;
+                }
+            };
+
+            // X coordinate of the next pixel which hasn't been passed to the target.
+            let next_x = Cell::new(x);
+            let mut first = Some(first);
+
+            let colors = iter::from_fn(|| {
+                let color = match first.take() {
+                    Some(color) => Some(color),
+                    None if next_x.get() < width => pixel(Point::new(next_x.get(), y)),
+                    None => None,
+                };
+                next_x.set(next_x.get() + 1);
+
+                color
+            })
+            .fuse();
+
+            target.fill_contiguous(
+                &Rectangle::new(Point::new(x, y), Size::new((width - x) as u32, 1)),
+                colors,
+            )?;
+
+            // Targets don't need to consume colors which are outside of their drawing area.
+            if next_x.get() == x {
+                break;
+            }
+            x = next_x.get();
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw target adapter which is used to implement `ImageDrawable::draw_sub_image`.
+///
+/// Only pixels inside `area` are drawn and the drawn pixels are offset by `-area.top_left`.
+#[derive(Debug)]
+pub(crate) struct SubImageTarget<'a, D> {
+    parent: &'a mut D,
+    area: Rectangle,
+}
+
+impl<'a, D> SubImageTarget<'a, D> {
+    pub(crate) fn new(parent: &'a mut D, area: &Rectangle) -> Self {
+        Self {
+            parent,
+            area: *area,
+        }
+    }
+
+    fn to_parent(&self, rectangle: &Rectangle) -> Rectangle {
+        Rectangle::new(rectangle.top_left - self.area.top_left, rectangle.size)
+    }
+}
+
+impl<D> Dimensions for SubImageTarget<'_, D>
+where
+    D: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        let parent = self.parent.bounding_box();
+
+        Rectangle::new(parent.top_left + self.area.top_left, parent.size).intersection(&self.area)
+    }
+}
+
+impl<D> DrawTarget for SubImageTarget<'_, D>
+where
+    D: DrawTarget,
+{
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+
+        self.parent.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(p, _)| area.contains(*p))
+                .map(|Pixel(p, c)| Pixel(p - area.top_left, c)),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = area.intersection(&self.area);
+
+        if clipped == *area {
+            let area = self.to_parent(area);
+            self.parent.fill_contiguous(&area, colors)
+        } else if clipped.is_zero_sized() {
+            Ok(())
+        } else {
+            self.draw_iter(area.points().zip(colors).map(|(p, c)| Pixel(p, c)))
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.to_parent(&area.intersection(&self.area));
+        self.parent.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.area;
+        self.fill_solid(&area, color)
+    }
+}
+
+/// Draw target adapter which converts the drawn colors into the color type of the parent target.
+#[derive(Debug)]
+pub(crate) struct ColorConvertedTarget<'a, D, C> {
+    parent: &'a mut D,
+    color_type: PhantomData<C>,
+}
+
+impl<'a, D, C> ColorConvertedTarget<'a, D, C> {
+    pub(crate) fn new(parent: &'a mut D) -> Self {
+        Self {
+            parent,
+            color_type: PhantomData,
+        }
+    }
+}
+
+impl<D, C> Dimensions for ColorConvertedTarget<'_, D, C>
+where
+    D: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.parent.bounding_box()
+    }
+}
+
+impl<D, C> DrawTarget for ColorConvertedTarget<'_, D, C>
+where
+    D: DrawTarget,
+    C: PixelColor + Into<D::Color>,
+{
+    type Color = C;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.parent
+            .draw_iter(pixels.into_iter().map(|Pixel(p, c)| Pixel(p, c.into())))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.parent
+            .fill_contiguous(area, colors.into_iter().map(Into::into))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.parent.fill_solid(area, color.into())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.parent.clear(color.into())
+    }
+}
@@ -1,15 +1,19 @@
 use core::marker::PhantomData;
 
-use embedded_graphics::{
+use super::embedded_graphics_core::{
     pixelcolor::{Gray8, PixelColor, Rgb555, Rgb565, Rgb888},
     prelude::*,
     primitives::Rectangle,
 };
 
-use crate::{
-    header::{Bpp, ChannelMasks},
+use super::{
+    dither::{DitherColor, FloydSteinberg, OrderedDither, Threshold},
+    draw::{ColorConvertedTarget, SubImageTarget},
     raw_bmp::RawBmp,
-    ParseError,
+    scale::Scaled,
+    solid_runs::SolidRuns,
+    transform::{Rotation, Transformed},
+    GetPixel, Limits, ParseError, RawPixel,
 };
 
 /// Dynamic BMP image.
@@ -19,11 +23,15 @@ use crate::{
 /// [`Bmp`] for improved performance.
 ///
 /// `DynamicBmp` works for all embedded-graphics draw targets that use a color type that implements
-/// `From` for `Rgb555, `Rgb565`, `Rgb888` and `Gray8`, like every `Rgb...` and `Bgr...` type
-/// included in embedded-graphics.
+/// `From` for `Rgb555, `Rgb565`, `Rgb888` and `Gray8`, like every color type included in
+/// embedded-graphics. Conversions to `BinaryColor`, `Gray2` and `Gray4` are based on the
+/// luminance of the image. To use a different luminance threshold for monochrome displays use
+/// [`threshold`] or use one of the dithering adapters.
 ///
 /// [`Bmp`]: struct.Bmp.html
+/// [`threshold`]: #method.threshold
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct DynamicBmp<'a, C> {
     raw_bmp: RawBmp<'a>,
     color_type: ColorType,
@@ -36,38 +44,43 @@ where
 {
     /// Creates a bitmap object from a byte slice.
     pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
-        let raw_bmp = RawBmp::from_slice(bytes)?;
-
-        let color_type = match raw_bmp.color_bpp() {
-            Bpp::Bits1 => return Err(ParseError::UnsupportedDynamicBmpFormat),
-            Bpp::Bits8 => ColorType::Gray8,
-            Bpp::Bits16 => {
-                if let Some(masks) = raw_bmp.header().channel_masks {
-                    match masks {
-                        ChannelMasks::RGB555 => ColorType::Rgb555,
-                        ChannelMasks::RGB565 => ColorType::Rgb565,
-                        _ => return Err(ParseError::UnsupportedDynamicBmpFormat),
-                    }
-                } else {
-                    // According to the GDI docs the default 16 bpp color format is Rgb555 if no
-                    // color masks are defined:
-                    // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-bitmapinfoheader
-                    ColorType::Rgb555
-                }
-            }
-            Bpp::Bits24 => ColorType::Rgb888,
-            Bpp::Bits32 => {
-                if let Some(masks) = raw_bmp.header().channel_masks {
-                    if masks == ChannelMasks::RGB888 {
-                        ColorType::Rgb888
-                    } else {
-                        return Err(ParseError::UnsupportedDynamicBmpFormat);
-                    }
-                } else {
-                    ColorType::Rgb888
-                }
-            }
-        };
+        Self::from_raw(RawBmp::from_slice(bytes)?)
+    }
+
+    /// Creates a bitmap object from a byte slice and checks that the image doesn't exceed the
+    /// given limits.
+    ///
+    /// See [`Limits`] for more information.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_slice_with_limits(bytes, limits)?)
+    }
+
+    /// Creates a bitmap object from a byte slice which contains a BMP image without a file
+    /// header.
+    ///
+    /// See [`RawBmp::from_dib_slice`] for more information about the supported data layout.
+    ///
+    /// [`RawBmp::from_dib_slice`]: struct.RawBmp.html#method.from_dib_slice
+    pub fn from_dib_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_dib_slice(bytes)?)
+    }
+
+    /// Creates a bitmap object from a byte slice which contains a BMP image without a file
+    /// header and checks that the image doesn't exceed the given limits.
+    ///
+    /// See [`RawBmp::from_dib_slice`] and [`Limits`] for more information.
+    ///
+    /// [`RawBmp::from_dib_slice`]: struct.RawBmp.html#method.from_dib_slice
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_dib_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        Self::from_raw(RawBmp::from_dib_slice_with_limits(bytes, limits)?)
+    }
+
+    /// Creates a bitmap object from a parsed raw image.
+    pub(crate) fn from_raw(raw_bmp: RawBmp<'a>) -> Result<Self, ParseError> {
+        let color_type = raw_bmp.detect_color_type()?;
 
         Ok(Self {
             raw_bmp,
@@ -76,6 +89,11 @@ where
         })
     }
 
+    /// Returns the color type which was detected for this image.
+    pub fn color_type(&self) -> ColorType {
+        self.color_type
+    }
+
     /// Returns a reference to the raw BMP image.
     ///
     /// The [`RawBmp`] instance can be used to access lower level information about the BMP file.
@@ -84,6 +102,134 @@ where
     pub fn as_raw(&self) -> &RawBmp<'a> {
         &self.raw_bmp
     }
+
+    /// Returns the color of the pixel at the given point.
+    ///
+    /// `None` is returned if `p` is outside the image.
+    pub fn pixel(&self, p: Point) -> Option<C> {
+        self.raw_to_color(self.raw_bmp.pixel(p)?)
+    }
+
+    /// Converts a raw pixel value into a color.
+    fn raw_to_color(&self, raw: u32) -> Option<C> {
+        match self.color_type {
+            ColorType::Rgb555 => self.raw_bmp.raw_to_color::<Rgb555>(raw).map(Into::into),
+            ColorType::Rgb565 => self.raw_bmp.raw_to_color::<Rgb565>(raw).map(Into::into),
+            ColorType::Rgb888 => self.raw_bmp.raw_to_color::<Rgb888>(raw).map(Into::into),
+            ColorType::Gray8 => self.raw_bmp.raw_to_color::<Gray8>(raw).map(Into::into),
+            ColorType::Gray16 => Some(self.raw_bmp.gray16_to_gray8(raw).into()),
+        }
+    }
+
+    /// Returns a view of this image which is converted to `BinaryColor` by using a luminance
+    /// threshold.
+    ///
+    /// The luminance is calculated from the original image data and is independent of the color
+    /// type `C`. See [`Threshold`] for more information.
+    ///
+    /// [`Threshold`]: struct.Threshold.html
+    pub fn threshold(&self, threshold: u8) -> Threshold<DynamicBmp<'a, Gray8>> {
+        Threshold::new(self.with_color_type(), threshold)
+    }
+
+    /// Returns a view of this image which uses ordered dithering to reduce the color depth.
+    ///
+    /// The dithering is based on the luminance of the original image data and is independent of
+    /// the color type `C`. See [`OrderedDither`] for more information.
+    ///
+    /// [`OrderedDither`]: struct.OrderedDither.html
+    pub fn ordered_dither<O>(&self) -> OrderedDither<DynamicBmp<'a, Gray8>, O>
+    where
+        O: DitherColor,
+    {
+        OrderedDither::new(self.with_color_type())
+    }
+
+    /// Returns a view of this image which uses Floyd-Steinberg dithering to reduce the color
+    /// depth.
+    ///
+    /// The dithering is based on the luminance of the original image data and is independent of
+    /// the color type `C`. `buffer` must contain at least one entry per pixel in a row of the
+    /// image, otherwise `None` is returned. See [`FloydSteinberg`] for more information.
+    ///
+    /// [`FloydSteinberg`]: struct.FloydSteinberg.html
+    pub fn floyd_steinberg<'b, O>(
+        &self,
+        buffer: &'b mut [i16],
+    ) -> Option<FloydSteinberg<'b, DynamicBmp<'a, Gray8>, O>>
+    where
+        O: DitherColor,
+    {
+        FloydSteinberg::new(self.with_color_type(), buffer)
+    }
+
+    /// Returns a view of this image which is rotated clockwise.
+    pub fn rotated(&self, rotation: Rotation) -> Transformed<Self> {
+        Transformed::new(*self).rotated(rotation)
+    }
+
+    /// Returns a view of this image which is flipped horizontally.
+    pub fn flipped_horizontal(&self) -> Transformed<Self> {
+        Transformed::new(*self).flipped_horizontal()
+    }
+
+    /// Returns a view of this image which is flipped vertically.
+    pub fn flipped_vertical(&self) -> Transformed<Self> {
+        Transformed::new(*self).flipped_vertical()
+    }
+
+    /// Returns a view of this image which is scaled by an integer factor.
+    pub fn scaled(&self, factor: u32) -> Scaled<Self> {
+        Scaled::new(*self, factor)
+    }
+
+    /// Returns a view of this image which is scaled to the given size.
+    ///
+    /// Nearest-neighbor sampling is used to scale the image.
+    pub fn scaled_to(&self, size: Size) -> Scaled<Self> {
+        Scaled::with_size(*self, size)
+    }
+
+    /// Returns a view of this image which draws runs of identical pixels with `fill_solid`.
+    ///
+    /// Runs which are at least `min_run_length` pixels long are drawn with `fill_solid` and all
+    /// other pixels with `fill_contiguous`. See [`SolidRuns`] for more information.
+    ///
+    /// [`SolidRuns`]: struct.SolidRuns.html
+    pub fn solid_runs(&self, min_run_length: u32) -> SolidRuns<Self> {
+        SolidRuns::new(*self, min_run_length)
+    }
+}
+
+impl<'a, C> DynamicBmp<'a, C> {
+    /// Returns a copy of this image with a different target color type.
+    pub(crate) fn with_color_type<C2>(&self) -> DynamicBmp<'a, C2> {
+        DynamicBmp {
+            raw_bmp: self.raw_bmp,
+            color_type: self.color_type,
+            target_color_type: PhantomData,
+        }
+    }
+}
+
+impl<C> GetPixel for DynamicBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888> + From<Gray8>,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<C> {
+        DynamicBmp::pixel(self, p)
+    }
+
+    fn with_row_major_pixels<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut dyn FnMut(Point) -> Option<C>) -> R,
+    {
+        let mut pixel = self.raw_bmp.row_major_pixels();
+
+        f(&mut |p| pixel(p).and_then(|raw| self.raw_to_color(raw)))
+    }
 }
 
 impl<C> ImageDrawable for DynamicBmp<'_, C>
@@ -97,10 +243,24 @@ where
         D: DrawTarget<Color = C>,
     {
         match self.color_type {
-            ColorType::Rgb555 => self.raw_bmp.draw(&mut target.color_converted::<Rgb555>()),
-            ColorType::Rgb565 => self.raw_bmp.draw(&mut target.color_converted::<Rgb565>()),
-            ColorType::Rgb888 => self.raw_bmp.draw(&mut target.color_converted::<Rgb888>()),
-            ColorType::Gray8 => self.raw_bmp.draw(&mut target.color_converted::<Gray8>()),
+            ColorType::Rgb555 => self
+                .raw_bmp
+                .draw(&mut ColorConvertedTarget::<_, Rgb555>::new(target)),
+            ColorType::Rgb565 => self
+                .raw_bmp
+                .draw(&mut ColorConvertedTarget::<_, Rgb565>::new(target)),
+            ColorType::Rgb888 => self
+                .raw_bmp
+                .draw(&mut ColorConvertedTarget::<_, Rgb888>::new(target)),
+            ColorType::Gray8 => self
+                .raw_bmp
+                .draw(&mut ColorConvertedTarget::<_, Gray8>::new(target)),
+            ColorType::Gray16 => target.fill_contiguous(
+                &Rectangle::new(Point::zero(), self.size()),
+                self.raw_bmp
+                    .pixels()
+                    .map(|RawPixel { color, .. }| self.raw_bmp.gray16_to_gray8(color).into()),
+            ),
         }
     }
 
@@ -108,7 +268,7 @@ where
     where
         D: DrawTarget<Color = Self::Color>,
     {
-        self.draw(&mut target.translated(-area.top_left).clipped(area))
+        self.draw(&mut SubImageTarget::new(target, area))
     }
 }
 
@@ -121,10 +281,29 @@ where
     }
 }
 
+/// Color type of a BMP image.
+///
+/// The color type is detected by [`RawBmp::detect_color_type`] and is used by [`DynamicBmp`] to
+/// decode the image data. Images which use a color table report the color type which is used to
+/// decode the color table entries.
+///
+/// [`RawBmp::detect_color_type`]: struct.RawBmp.html#method.detect_color_type
+/// [`DynamicBmp`]: struct.DynamicBmp.html
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-enum ColorType {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ColorType {
+    /// RGB555 color.
     Rgb555,
+    /// RGB565 color.
     Rgb565,
+    /// RGB888 color.
     Rgb888,
+    /// 8 bit grayscale.
     Gray8,
+    /// 16 bit grayscale.
+    ///
+    /// The grayscale value is defined by a channel mask, which is used for all color channels. The
+    /// value is converted to `Gray8` when the image is drawn.
+    Gray16,
 }
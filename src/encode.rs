@@ -0,0 +1,90 @@
+//! BMP encoder for images from the `image` crate.
+
+use std::vec::Vec;
+
+use image::{Rgb, RgbImage};
+
+/// Size of the BMP file header in bytes.
+const FILE_HEADER_SIZE: u32 = 14;
+
+/// Size of the `BITMAPINFOHEADER` in bytes.
+const INFO_HEADER_SIZE: u32 = 40;
+
+/// Error returned by [`encode_rgb_image`].
+///
+/// [`encode_rgb_image`]: fn.encode_rgb_image.html
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The image is too large to be stored in a BMP file.
+    ImageTooLarge,
+}
+
+/// Encodes an `image::RgbImage` as a 24 BPP BMP file.
+///
+/// The returned data can be loaded by [`RawBmp::from_slice`], [`Bmp::from_slice`] or
+/// [`DynamicBmp::from_slice`].
+///
+/// [`EncodeError::ImageTooLarge`] is returned if the width or height of the image exceeds
+/// `i32::MAX` or if the file would be larger than `u32::MAX` bytes, because these values can't be
+/// stored in the BMP header.
+///
+/// [`RawBmp::from_slice`]: struct.RawBmp.html#method.from_slice
+/// [`Bmp::from_slice`]: struct.Bmp.html#method.from_slice
+/// [`DynamicBmp::from_slice`]: struct.DynamicBmp.html#method.from_slice
+/// [`EncodeError::ImageTooLarge`]: enum.EncodeError.html#variant.ImageTooLarge
+pub fn encode_rgb_image(image: &RgbImage) -> Result<Vec<u8>, EncodeError> {
+    let (width, height) = image.dimensions();
+
+    let signed_width = i32::try_from(width).map_err(|_| EncodeError::ImageTooLarge)?;
+    let signed_height = i32::try_from(height).map_err(|_| EncodeError::ImageTooLarge)?;
+
+    // Each row is padded to a multiple of 4 bytes.
+    let bytes_per_row = (u64::from(width) * 3).div_ceil(4) * 4;
+    let image_data_len =
+        u32::try_from(bytes_per_row * u64::from(height)).map_err(|_| EncodeError::ImageTooLarge)?;
+    let image_data_start = FILE_HEADER_SIZE + INFO_HEADER_SIZE;
+    let file_size = image_data_start
+        .checked_add(image_data_len)
+        .ok_or(EncodeError::ImageTooLarge)?;
+
+    // The row length is smaller than the file size, which fits into an `u32`.
+    let bytes_per_row = bytes_per_row as usize;
+
+    let mut data = Vec::with_capacity(file_size as usize);
+
+    // File header
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&file_size.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&image_data_start.to_le_bytes());
+
+    // DIB header
+    data.extend_from_slice(&INFO_HEADER_SIZE.to_le_bytes());
+    data.extend_from_slice(&signed_width.to_le_bytes());
+    data.extend_from_slice(&signed_height.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes()); // color planes
+    data.extend_from_slice(&24u16.to_le_bytes()); // bits per pixel
+    data.extend_from_slice(&0u32.to_le_bytes()); // compression method
+    data.extend_from_slice(&image_data_len.to_le_bytes());
+    data.extend_from_slice(&2835u32.to_le_bytes()); // 72 DPI
+    data.extend_from_slice(&2835u32.to_le_bytes()); // 72 DPI
+    data.extend_from_slice(&0u32.to_le_bytes()); // colors used
+    data.extend_from_slice(&0u32.to_le_bytes()); // colors important
+
+    // Image data in bottom-up row order
+    for y in (0..height).rev() {
+        let row_start = data.len();
+
+        for x in 0..width {
+            let Rgb([r, g, b]) = *image.get_pixel(x, y);
+            data.extend_from_slice(&[b, g, r]);
+        }
+
+        data.resize(row_start + bytes_per_row, 0);
+    }
+
+    Ok(data)
+}
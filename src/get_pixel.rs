@@ -0,0 +1,43 @@
+use super::embedded_graphics_core::prelude::*;
+
+/// Random access to the pixels of an image.
+///
+/// This trait is implemented by [`Bmp`] and [`DynamicBmp`] and is used by the image adapters in
+/// this crate, like [`Transformed`], to read pixels in an arbitrary order directly from the BMP
+/// image data.
+///
+/// Accessing individual pixels in compressed images is slow, because all previous rows in the image
+/// data need to be decoded for each pixel. Adapters which read all pixels in row-major order, like
+/// [`SolidRuns`], use [`with_row_major_pixels`] instead, which continues decoding from the previous
+/// pixel.
+///
+/// [`Bmp`]: struct.Bmp.html
+/// [`DynamicBmp`]: struct.DynamicBmp.html
+/// [`Transformed`]: struct.Transformed.html
+/// [`SolidRuns`]: struct.SolidRuns.html
+/// [`with_row_major_pixels`]: #method.with_row_major_pixels
+pub trait GetPixel: OriginDimensions {
+    /// The color type.
+    type Color: PixelColor;
+
+    /// Returns the color of the pixel at the given point.
+    ///
+    /// `None` is returned if `p` is outside the image or if the pixel is transparent.
+    fn pixel(&self, p: Point) -> Option<Self::Color>;
+
+    /// Calls `f` with a function which returns the colors of pixels that are read in row-major
+    /// order.
+    ///
+    /// The function passed to `f` returns the same colors as [`pixel`], but the points must be
+    /// passed in row-major order. Images with compressed image data use this to avoid decoding all
+    /// previous rows for each pixel. The default implementation uses [`pixel`].
+    ///
+    /// [`pixel`]: #tymethod.pixel
+    fn with_row_major_pixels<R, F>(&self, f: F) -> R
+    where
+        Self: Sized,
+        F: FnOnce(&mut dyn FnMut(Point) -> Option<Self::Color>) -> R,
+    {
+        f(&mut |p| self.pixel(p))
+    }
+}
@@ -0,0 +1,214 @@
+//! Color space information of V4 and V5 DIB headers.
+
+use super::super::{
+    parser::{le_i32, le_u32},
+    ParseError,
+};
+
+/// `LCS_CALIBRATED_RGB`
+const LCS_CALIBRATED_RGB: u32 = 0;
+/// `LCS_sRGB`
+const LCS_SRGB: u32 = u32::from_be_bytes(*b"sRGB");
+/// `LCS_WINDOWS_COLOR_SPACE`
+const LCS_WINDOWS_COLOR_SPACE: u32 = u32::from_be_bytes(*b"Win ");
+/// `PROFILE_LINKED`
+const PROFILE_LINKED: u32 = u32::from_be_bytes(*b"LINK");
+/// `PROFILE_EMBEDDED`
+const PROFILE_EMBEDDED: u32 = u32::from_be_bytes(*b"MBED");
+
+/// Color space of an image.
+///
+/// The color space is only included in images which use a V4 or V5 DIB header.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ColorSpace {
+    /// Calibrated RGB color space (`LCS_CALIBRATED_RGB`).
+    ///
+    /// The color space is defined by the CIE XYZ endpoints and gamma values.
+    CalibratedRgb(CalibratedRgb),
+
+    /// sRGB color space (`LCS_sRGB`).
+    Srgb,
+
+    /// Default color space of the system (`LCS_WINDOWS_COLOR_SPACE`).
+    WindowsColorSpace,
+
+    /// The color space is defined by a linked ICC profile (`PROFILE_LINKED`).
+    LinkedProfile,
+
+    /// The color space is defined by an embedded ICC profile (`PROFILE_EMBEDDED`).
+    EmbeddedProfile,
+
+    /// Unknown color space type.
+    Unknown(u32),
+}
+
+impl ColorSpace {
+    pub(crate) fn parse(input: &[u8]) -> Result<(&[u8], Self), ParseError> {
+        let (input, color_space_type) = le_u32(input)?;
+        let (input, red) = CieXyz::parse(input)?;
+        let (input, green) = CieXyz::parse(input)?;
+        let (input, blue) = CieXyz::parse(input)?;
+        let (input, gamma_red) = le_u32(input)?;
+        let (input, gamma_green) = le_u32(input)?;
+        let (input, gamma_blue) = le_u32(input)?;
+
+        let color_space = match color_space_type {
+            LCS_CALIBRATED_RGB => Self::CalibratedRgb(CalibratedRgb {
+                red,
+                green,
+                blue,
+                gamma_red,
+                gamma_green,
+                gamma_blue,
+            }),
+            LCS_SRGB => Self::Srgb,
+            LCS_WINDOWS_COLOR_SPACE => Self::WindowsColorSpace,
+            PROFILE_LINKED => Self::LinkedProfile,
+            PROFILE_EMBEDDED => Self::EmbeddedProfile,
+            _ => Self::Unknown(color_space_type),
+        };
+
+        Ok((input, color_space))
+    }
+}
+
+/// Calibrated RGB color space.
+///
+/// The endpoints use a 2.30 fixed point format and the gamma values use an unsigned 16.16 fixed
+/// point format, as stored in the BMP file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CalibratedRgb {
+    /// CIE XYZ coordinates of the red endpoint.
+    pub red: CieXyz,
+    /// CIE XYZ coordinates of the green endpoint.
+    pub green: CieXyz,
+    /// CIE XYZ coordinates of the blue endpoint.
+    pub blue: CieXyz,
+    /// Gamma of the red channel.
+    pub gamma_red: u32,
+    /// Gamma of the green channel.
+    pub gamma_green: u32,
+    /// Gamma of the blue channel.
+    pub gamma_blue: u32,
+}
+
+/// CIE XYZ coordinates.
+///
+/// The coordinates use a 2.30 fixed point format.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CieXyz {
+    /// X coordinate.
+    pub x: i32,
+    /// Y coordinate.
+    pub y: i32,
+    /// Z coordinate.
+    pub z: i32,
+}
+
+impl CieXyz {
+    fn parse(input: &[u8]) -> Result<(&[u8], Self), ParseError> {
+        let (input, x) = le_i32(input)?;
+        let (input, y) = le_i32(input)?;
+        let (input, z) = le_i32(input)?;
+
+        Ok((input, Self { x, y, z }))
+    }
+}
+
+/// `LCS_GM_BUSINESS`
+const LCS_GM_BUSINESS: u32 = 1;
+/// `LCS_GM_GRAPHICS`
+const LCS_GM_GRAPHICS: u32 = 2;
+/// `LCS_GM_IMAGES`
+const LCS_GM_IMAGES: u32 = 4;
+/// `LCS_GM_ABS_COLORIMETRIC`
+const LCS_GM_ABS_COLORIMETRIC: u32 = 8;
+
+/// Rendering intent.
+///
+/// The rendering intent is only included in images which use a V5 DIB header.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum RenderingIntent {
+    /// Saturation (`LCS_GM_BUSINESS`).
+    Saturation,
+    /// Relative colorimetric (`LCS_GM_GRAPHICS`).
+    RelativeColorimetric,
+    /// Perceptual (`LCS_GM_IMAGES`).
+    Perceptual,
+    /// Absolute colorimetric (`LCS_GM_ABS_COLORIMETRIC`).
+    AbsoluteColorimetric,
+    /// Unknown rendering intent.
+    Unknown(u32),
+}
+
+impl RenderingIntent {
+    pub(crate) fn parse(input: &[u8]) -> Result<(&[u8], Self), ParseError> {
+        let (input, value) = le_u32(input)?;
+
+        let intent = match value {
+            LCS_GM_BUSINESS => Self::Saturation,
+            LCS_GM_GRAPHICS => Self::RelativeColorimetric,
+            LCS_GM_IMAGES => Self::Perceptual,
+            LCS_GM_ABS_COLORIMETRIC => Self::AbsoluteColorimetric,
+            _ => Self::Unknown(value),
+        };
+
+        Ok((input, intent))
+    }
+}
+
+/// ICC color profile.
+///
+/// See the [`RawBmp::icc_profile`] method documentation for more information.
+///
+/// [`RawBmp::icc_profile`]: struct.RawBmp.html#method.icc_profile
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IccProfile<'a> {
+    /// ICC profile data which is embedded in the BMP file.
+    Embedded(&'a [u8]),
+
+    /// File name of a linked ICC profile.
+    ///
+    /// The file name is stored as it is included in the BMP file, which should use the Windows
+    /// code page 1252 encoding. The terminating null character isn't included in the slice.
+    Linked(&'a [u8]),
+}
+
+impl<'a> IccProfile<'a> {
+    /// Extracts the ICC profile from the data following the start of the DIB header.
+    pub(crate) fn parse(
+        color_space: ColorSpace,
+        dib_header: &'a [u8],
+        offset: u32,
+        size: u32,
+    ) -> Result<Option<Self>, ParseError> {
+        let data = || {
+            let start = offset as usize;
+            let end = start
+                .checked_add(size as usize)
+                .ok_or(ParseError::UnexpectedEndOfFile)?;
+
+            dib_header
+                .get(start..end)
+                .ok_or(ParseError::UnexpectedEndOfFile)
+        };
+
+        Ok(match color_space {
+            ColorSpace::EmbeddedProfile => Some(Self::Embedded(data()?)),
+            ColorSpace::LinkedProfile => {
+                let data = data()?;
+                let length = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+
+                Some(Self::Linked(&data[..length]))
+            }
+            _ => None,
+        })
+    }
+}
@@ -1,17 +1,21 @@
 //! Device Independent Bitmap (DIB) header.
 
-use embedded_graphics::prelude::*;
+use super::super::embedded_graphics_core::prelude::*;
 
-use crate::{
-    header::CompressionMethod,
+use super::super::{
+    header::{ColorSpace, CompressionMethod, RenderingIntent, Resolution},
     parser::{le_i32, le_u16, le_u32, take_slice},
     Bpp, ChannelMasks, ParseError, RowOrder,
 };
 
+const DIB_CORE_HEADER_SIZE: u32 = 12;
 const DIB_INFO_HEADER_SIZE: u32 = 40;
+const DIB_V2_HEADER_SIZE: u32 = 52;
 const DIB_V3_HEADER_SIZE: u32 = 56;
 const DIB_V4_HEADER_SIZE: u32 = 108;
 const DIB_V5_HEADER_SIZE: u32 = 124;
+const DIB_OS2_V2_HEADER_SIZE: u32 = 64;
+const DIB_OS2_V2_MIN_HEADER_SIZE: u32 = 16;
 
 /// Device Independent Bitmap (DIB) header.
 #[derive(Debug)]
@@ -23,6 +27,16 @@ pub struct DibHeader {
     pub channel_masks: Option<ChannelMasks>,
     pub header_type: HeaderType,
     pub row_order: RowOrder,
+    pub color_planes: u16,
+    pub resolution: Resolution,
+    pub colors_used: u32,
+    pub colors_important: u32,
+    pub color_space: Option<ColorSpace>,
+    pub rendering_intent: Option<RenderingIntent>,
+    /// Offset of the ICC profile data, relative to the start of the DIB header.
+    pub profile_data: u32,
+    /// Size of the ICC profile data in bytes.
+    pub profile_size: u32,
     /// Entry length of color table (NOT length in bytes)
     pub color_table_num_entries: u32,
 }
@@ -41,53 +55,136 @@ impl DibHeader {
         // Add 4 back on so the constants remain the correct size relative to the BMP
         // documentation/specs.
         let header_type = match dib_header_length {
+            DIB_CORE_HEADER_SIZE => HeaderType::Core,
+            DIB_V2_HEADER_SIZE => HeaderType::V2,
             DIB_V3_HEADER_SIZE => HeaderType::V3,
             DIB_V4_HEADER_SIZE => HeaderType::V4,
             DIB_V5_HEADER_SIZE => HeaderType::V5,
             DIB_INFO_HEADER_SIZE => HeaderType::Info,
+            // OS/2 2.x headers can be truncated to any length of at least 16 bytes. The lengths of
+            // the Windows headers, which are matched above, take precedence.
+            DIB_OS2_V2_MIN_HEADER_SIZE..=DIB_OS2_V2_HEADER_SIZE => HeaderType::Os2V2,
             _ => return Err(ParseError::UnsupportedHeaderLength(dib_header_length)),
         };
 
-        // Fields common to all DIB variants
+        if header_type == HeaderType::Core {
+            return Self::parse_core(input, dib_header_data);
+        }
+
+        // The missing fields of truncated OS/2 2.x headers are set to zero.
+        let mut os2_header_data = [0; DIB_OS2_V2_HEADER_SIZE as usize - 4];
+        let dib_header_data = if header_type == HeaderType::Os2V2 {
+            os2_header_data[..dib_header_data.len()].copy_from_slice(dib_header_data);
+            &os2_header_data[..]
+        } else {
+            dib_header_data
+        };
+
+        // Fields common to all DIB variants. The first 40 bytes of OS/2 2.x headers use the same
+        // layout as a `BITMAPINFOHEADER`.
         let (dib_header_data, image_width) = le_u32(dib_header_data)?;
         let (dib_header_data, image_height) = le_i32(dib_header_data)?;
-        let (dib_header_data, _color_planes) = le_u16(dib_header_data)?;
-        let (dib_header_data, bpp) = Bpp::parse(dib_header_data)?;
+        let (dib_header_data, color_planes) = le_u16(dib_header_data)?;
+        let (dib_header_data, bpp) = le_u16(dib_header_data)?;
 
         // Extra fields defined by DIB variants
         // Variants are described in
         // <https://www.liquisearch.com/bmp_file_format/file_structure/dib_header_bitmap_information_header>
         // and <https://docs.microsoft.com/en-us/windows/win32/gdi/bitmap-header-types>
-        let (dib_header_data, compression_method) = CompressionMethod::parse(dib_header_data)?;
+        let (dib_header_data, compression_method) = le_u32(dib_header_data)?;
+        let compression_method = CompressionMethod::new(compression_method, header_type)?;
+
+        // The bit depth of images which contain a JPEG or PNG stream can be implied by the stream.
+        let bpp = if bpp == 0 && compression_method.is_embedded_stream() {
+            Bpp::Implied
+        } else {
+            Bpp::new(bpp)?
+        };
+
+        // Huffman 1D and RLE compression are only defined for a single bit depth.
+        let required_bpp = match compression_method {
+            CompressionMethod::Huffman1d => Some(Bpp::Bits1),
+            CompressionMethod::Rle4 | CompressionMethod::CmykRle4 => Some(Bpp::Bits4),
+            CompressionMethod::Rle8 | CompressionMethod::CmykRle8 => Some(Bpp::Bits8),
+            _ => None,
+        };
+        if required_bpp.is_some_and(|required_bpp| required_bpp != bpp) {
+            return Err(ParseError::UnsupportedBpp(bpp.bits()));
+        }
         let (dib_header_data, image_data_len) = le_u32(dib_header_data)?;
-        let (dib_header_data, _pels_per_meter_x) = le_u32(dib_header_data)?;
-        let (dib_header_data, _pels_per_meter_y) = le_u32(dib_header_data)?;
+        let (dib_header_data, pixels_per_meter_x) = le_u32(dib_header_data)?;
+        let (dib_header_data, pixels_per_meter_y) = le_u32(dib_header_data)?;
         let (dib_header_data, colors_used) = le_u32(dib_header_data)?;
-        let (dib_header_data, _colors_important) = le_u32(dib_header_data)?;
+        let (dib_header_data, colors_important) = le_u32(dib_header_data)?;
 
-        let (_dib_header_data, channel_masks) = if header_type.is_at_least(HeaderType::V3)
-            && compression_method == CompressionMethod::Bitfields
-        {
+        let (dib_header_data, channel_masks) = if header_type.is_at_least(HeaderType::V2) {
             let (dib_header_data, mask_red) = le_u32(dib_header_data)?;
             let (dib_header_data, mask_green) = le_u32(dib_header_data)?;
             let (dib_header_data, mask_blue) = le_u32(dib_header_data)?;
-            let (dib_header_data, mask_alpha) = le_u32(dib_header_data)?;
+
+            // `BITMAPV2INFOHEADER`s don't include the alpha mask.
+            let (dib_header_data, mask_alpha) = if header_type.is_at_least(HeaderType::V3) {
+                le_u32(dib_header_data)?
+            } else {
+                (dib_header_data, 0)
+            };
+
+            // The masks are only used if the bitfields compression method is selected.
+            let channel_masks =
+                (compression_method == CompressionMethod::Bitfields).then_some(ChannelMasks {
+                    red: mask_red,
+                    green: mask_green,
+                    blue: mask_blue,
+                    alpha: mask_alpha,
+                });
+
+            (dib_header_data, channel_masks)
+        } else {
+            (dib_header_data, None)
+        };
+
+        // `BITMAPINFOHEADER`s don't include the channel masks. Images which use the bitfields
+        // compression method store the RGB masks directly after the header instead.
+        let (input, channel_masks) = if header_type == HeaderType::Info
+            && compression_method == CompressionMethod::Bitfields
+        {
+            let (input, mask_red) = le_u32(input)?;
+            let (input, mask_green) = le_u32(input)?;
+            let (input, mask_blue) = le_u32(input)?;
 
             (
-                dib_header_data,
+                input,
                 Some(ChannelMasks {
                     red: mask_red,
                     green: mask_green,
                     blue: mask_blue,
-                    alpha: mask_alpha,
+                    alpha: 0,
                 }),
             )
+        } else {
+            (input, channel_masks)
+        };
+
+        let (dib_header_data, color_space) = if header_type.is_at_least(HeaderType::V4) {
+            let (dib_header_data, color_space) = ColorSpace::parse(dib_header_data)?;
+            (dib_header_data, Some(color_space))
         } else {
             (dib_header_data, None)
         };
 
+        let (rendering_intent, profile_data, profile_size) =
+            if header_type.is_at_least(HeaderType::V5) {
+                let (dib_header_data, rendering_intent) = RenderingIntent::parse(dib_header_data)?;
+                let (dib_header_data, profile_data) = le_u32(dib_header_data)?;
+                let (_dib_header_data, profile_size) = le_u32(dib_header_data)?;
+
+                (Some(rendering_intent), profile_data, profile_size)
+            } else {
+                (None, 0, 0)
+            };
+
         let color_table_num_entries: u32 = if colors_used == 0 {
-            if bpp.bits() < 16 {
+            if bpp.bits() < 16 && !compression_method.is_embedded_stream() {
                 2u32.pow(bpp.bits().into())
             } else {
                 0
@@ -106,28 +203,116 @@ impl DibHeader {
             input,
             Self {
                 header_type,
-                image_size: Size::new(image_width, image_height.abs() as u32),
+                image_size: Size::new(image_width, image_height.unsigned_abs()),
                 image_data_len,
                 bpp,
                 channel_masks,
                 compression: compression_method,
                 row_order,
+                color_planes,
+                resolution: Resolution {
+                    pixels_per_meter_x,
+                    pixels_per_meter_y,
+                },
+                colors_used,
+                colors_important,
+                color_space,
+                rendering_intent,
+                profile_data,
+                profile_size,
+                color_table_num_entries,
+            },
+        ))
+    }
+
+    /// Parses the fields of a `BITMAPCOREHEADER`.
+    ///
+    /// Core headers use 16 bit dimensions and don't support compression or top-down images.
+    fn parse_core<'a>(
+        input: &'a [u8],
+        dib_header_data: &[u8],
+    ) -> Result<(&'a [u8], Self), ParseError> {
+        let (dib_header_data, image_width) = le_u16(dib_header_data)?;
+        let (dib_header_data, image_height) = le_u16(dib_header_data)?;
+        let (dib_header_data, color_planes) = le_u16(dib_header_data)?;
+        let (_dib_header_data, bpp) = le_u16(dib_header_data)?;
+
+        let bpp = Bpp::new(bpp)?;
+
+        // The color table of images with a core header always contains all colors.
+        let color_table_num_entries = if bpp.bits() < 16 {
+            2u32.pow(bpp.bits().into())
+        } else {
+            0
+        };
+
+        Ok((
+            input,
+            Self {
+                header_type: HeaderType::Core,
+                image_size: Size::new(image_width.into(), image_height.into()),
+                image_data_len: 0,
+                bpp,
+                channel_masks: None,
+                compression: CompressionMethod::Rgb,
+                row_order: RowOrder::BottomUp,
+                color_planes,
+                resolution: Resolution::default(),
+                colors_used: 0,
+                colors_important: 0,
+                color_space: None,
+                rendering_intent: None,
+                profile_data: 0,
+                profile_size: 0,
                 color_table_num_entries,
             },
         ))
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// DIB header type.
+///
+/// The header type is determined by the length of the DIB header.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum HeaderType {
+    /// `BITMAPCOREHEADER` (12 bytes).
+    ///
+    /// This header is also used by OS/2 1.x bitmaps.
+    Core,
+    /// `BITMAPINFOHEADER` (40 bytes).
     Info,
+    /// `BITMAPV2INFOHEADER` (52 bytes).
+    V2,
+    /// `BITMAPV3INFOHEADER` (56 bytes).
     V3,
+    /// `BITMAPV4HEADER` (108 bytes).
     V4,
+    /// `BITMAPV5HEADER` (124 bytes).
     V5,
+    /// OS/2 2.x `BITMAPINFOHEADER2` (16 to 64 bytes).
+    ///
+    /// Truncated headers are accepted and the missing fields are treated as zero.
+    Os2V2,
 }
 
 impl HeaderType {
     fn is_at_least(self, header_type: HeaderType) -> bool {
-        self as u8 >= header_type as u8
+        self.version() >= header_type.version()
+    }
+
+    /// Returns the Windows header version, which determines the available fields.
+    fn version(self) -> u8 {
+        match self {
+            Self::Core => 0,
+            // OS/2 2.x headers don't include any of the fields which were added in later
+            // Windows header versions.
+            Self::Info | Self::Os2V2 => 1,
+            Self::V2 => 2,
+            Self::V3 => 3,
+            Self::V4 => 4,
+            Self::V5 => 5,
+        }
     }
 }
@@ -3,24 +3,36 @@
 //! Information gleaned from [wikipedia](https://en.wikipedia.org/wiki/BMP_file_format) and
 //! [this website](http://paulbourke.net/dataformats/bmp/)
 
-use embedded_graphics::prelude::*;
+use super::embedded_graphics_core::prelude::*;
 
-use crate::{
+use super::{
     color_table::ColorTable,
     parser::{le_u16, le_u32, take, take_slice},
     ParseError,
 };
 
+mod color_space;
 mod dib_header;
 
+pub use color_space::{CalibratedRgb, CieXyz, ColorSpace, IccProfile, RenderingIntent};
 use dib_header::DibHeader;
+pub use dib_header::HeaderType;
 
 /// Bits per pixel.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Bpp {
+    /// The bit depth is implied by an embedded JPEG or PNG stream.
+    ///
+    /// See [`RawBmp::embedded_stream`] for more information.
+    ///
+    /// [`RawBmp::embedded_stream`]: struct.RawBmp.html#method.embedded_stream
+    Implied,
     /// 1 bit per pixel.
     Bits1,
+    /// 4 bits per pixel.
+    Bits4,
     /// 8 bits per pixel.
     Bits8,
     /// 16 bits per pixel.
@@ -29,55 +41,65 @@ pub enum Bpp {
     Bits24,
     /// 32 bits per pixel.
     Bits32,
+    /// 64 bits per pixel.
+    ///
+    /// The color channels use a linear 16 bit fixed point format, which is tone mapped to `Rgb888`
+    /// when the image is read.
+    Bits64,
 }
 
 /// Image row order
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum RowOrder {
     /// Bottom-up (standard)
+    #[default]
     BottomUp,
     /// Top-down
     TopDown,
 }
 
-impl Default for RowOrder {
-    fn default() -> Self {
-        Self::BottomUp
-    }
-}
-
 impl Bpp {
     fn new(value: u16) -> Result<Self, ParseError> {
         Ok(match value {
             1 => Self::Bits1,
+            4 => Self::Bits4,
             8 => Self::Bits8,
             16 => Self::Bits16,
             24 => Self::Bits24,
             32 => Self::Bits32,
+            64 => Self::Bits64,
             _ => return Err(ParseError::UnsupportedBpp(value)),
         })
     }
 
-    fn parse(input: &[u8]) -> Result<(&[u8], Self), ParseError> {
-        le_u16(input).and_then(|(input, value)| Ok((input, Self::new(value)?)))
-    }
-
     /// Returns the number of bits.
+    ///
+    /// `0` is returned for [`Bpp::Implied`].
+    ///
+    /// [`Bpp::Implied`]: #variant.Implied
     pub fn bits(self) -> u16 {
         match self {
+            Self::Implied => 0,
             Self::Bits1 => 1,
+            Self::Bits4 => 4,
             Self::Bits8 => 8,
             Self::Bits16 => 16,
             Self::Bits24 => 24,
             Self::Bits32 => 32,
+            Self::Bits64 => 64,
         }
     }
 }
 
 /// BMP header information
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
 pub struct Header {
+    /// File type.
+    pub file_type: FileType,
+
     /// Total file size in bytes.
     pub file_size: u32,
 
@@ -98,63 +120,284 @@ pub struct Header {
 
     /// Row order of the image data within the file
     pub row_order: RowOrder,
+
+    /// DIB header type.
+    pub header_type: HeaderType,
+
+    /// Compression method.
+    pub compression_method: CompressionMethod,
+
+    /// Physical resolution of the image.
+    pub resolution: Resolution,
+
+    /// Number of color planes.
+    ///
+    /// This value should always be `1`, but isn't validated by the parser.
+    pub color_planes: u16,
+
+    /// Number of colors in the color table.
+    ///
+    /// A value of `0` indicates that the color table uses the maximum number of colors for the
+    /// image bit depth.
+    pub colors_used: u32,
+
+    /// Number of important colors.
+    ///
+    /// A value of `0` indicates that all colors are important.
+    pub colors_important: u32,
+
+    /// Color space.
+    ///
+    /// The color space is only available for images with a V4 or V5 DIB header.
+    pub color_space: Option<ColorSpace>,
+
+    /// Rendering intent.
+    ///
+    /// The rendering intent is only available for images with a V5 DIB header.
+    pub rendering_intent: Option<RenderingIntent>,
+}
+
+// `Size` doesn't implement `defmt::Format` in all supported embedded-graphics versions, which
+// prevents `Format` from being derived.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Header {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Header {{ file_type: {}, file_size: {=u32}, image_data_start: {=usize}, image_size: Size {{ width: {=u32}, height: {=u32} }}, bpp: {}, image_data_len: {=u32}, channel_masks: {}, row_order: {}, header_type: {}, compression_method: {}, resolution: {}, color_planes: {=u16}, colors_used: {=u32}, colors_important: {=u32}, color_space: {}, rendering_intent: {} }}",
+            self.file_type,
+            self.file_size,
+            self.image_data_start,
+            self.image_size.width,
+            self.image_size.height,
+            self.bpp,
+            self.image_data_len,
+            self.channel_masks,
+            self.row_order,
+            self.header_type,
+            self.compression_method,
+            self.resolution,
+            self.color_planes,
+            self.colors_used,
+            self.colors_important,
+            self.color_space,
+            self.rendering_intent,
+        )
+    }
 }
 
 impl Header {
+    #[allow(clippy::type_complexity)]
     pub(crate) fn parse(
         input: &[u8],
-    ) -> Result<(&[u8], (Header, Option<ColorTable<'_>>)), ParseError> {
+    ) -> Result<
+        (
+            &[u8],
+            (Header, Option<ColorTable<'_>>, Option<IccProfile<'_>>),
+        ),
+        ParseError,
+    > {
         // File header
         let (input, magic) = take::<2>(input)?;
-        if &magic != b"BM" {
-            return Err(ParseError::InvalidFileSignature);
-        }
+
+        // The header of a bitmap array entry is followed by the file header of the bitmap.
+        let (input, magic) = if &magic == b"BA" {
+            let (input, _) = take_slice(input, BITMAP_ARRAY_HEADER_SIZE - 2)?;
+            take::<2>(input)?
+        } else {
+            (input, magic)
+        };
+        let file_type = FileType::new(magic)?;
 
         let (input, file_size) = le_u32(input)?;
         let (input, _reserved_1) = le_u16(input)?;
         let (input, _reserved_2) = le_u16(input)?;
         let (input, image_data_start) = le_u32(input)?;
 
+        let (input, (header, color_table, icc_profile)) =
+            Self::parse_dib(input, file_size, Some(image_data_start as usize))?;
+
+        Ok((
+            input,
+            (
+                Header {
+                    file_type,
+                    ..header
+                },
+                color_table,
+                icc_profile,
+            ),
+        ))
+    }
+
+    /// Parses a DIB header and the following color table.
+    ///
+    /// If `image_data_start` is `None` the image data is expected to directly follow the color
+    /// table and the offset is calculated relative to the start of the DIB header.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn parse_dib(
+        input: &[u8],
+        file_size: u32,
+        image_data_start: Option<usize>,
+    ) -> Result<
+        (
+            &[u8],
+            (Header, Option<ColorTable<'_>>, Option<IccProfile<'_>>),
+        ),
+        ParseError,
+    > {
         // DIB header
+        let dib_header_start = input;
         let (input, dib_header) = DibHeader::parse(input)?;
 
+        // The ICC profile location is only included in V5 headers.
+        let icc_profile = match (dib_header.header_type, dib_header.color_space) {
+            (HeaderType::V5, Some(color_space)) => IccProfile::parse(
+                color_space,
+                dib_header_start,
+                dib_header.profile_data,
+                dib_header.profile_size,
+            )?,
+            _ => None,
+        };
+
         match dib_header.bpp {
             // Images with BPP <= 8 MUST include a color table
-            Bpp::Bits1 | Bpp::Bits8 if dib_header.color_table_num_entries == 0 => {
+            Bpp::Bits1 | Bpp::Bits4 | Bpp::Bits8
+                if dib_header.color_table_num_entries == 0
+                    && !dib_header.compression.is_embedded_stream() =>
+            {
                 return Err(ParseError::MissingColorTable);
             }
             _ => (),
         }
 
         let (input, color_table) = if dib_header.color_table_num_entries > 0 {
-            // Each color table entry is 4 bytes long
-            let (input, table) =
-                take_slice(input, dib_header.color_table_num_entries as usize * 4)?;
-            (input, Some(ColorTable::new(table)))
+            // Color table entries are 4 bytes long, except for core headers which use 3 byte
+            // entries. A table which is too large to be addressed can't be contained in the input.
+            let entry_len = if dib_header.header_type == HeaderType::Core {
+                3
+            } else {
+                4
+            };
+            let table_len = (dib_header.color_table_num_entries as usize)
+                .checked_mul(entry_len)
+                .ok_or(ParseError::UnexpectedEndOfFile)?;
+            let (input, table) = take_slice(input, table_len)?;
+            let cmyk = dib_header.compression.is_cmyk();
+            (input, Some(ColorTable::new(table, entry_len, cmyk)))
         } else {
             (input, None)
         };
 
-        Ok((
-            input,
-            (
-                Header {
-                    file_size,
-                    image_data_start: image_data_start as usize,
-                    image_size: dib_header.image_size,
-                    image_data_len: dib_header.image_data_len,
-                    bpp: dib_header.bpp,
-                    channel_masks: dib_header.channel_masks,
-                    row_order: dib_header.row_order,
-                },
-                color_table,
-            ),
-        ))
+        let image_data_start =
+            image_data_start.unwrap_or_else(|| dib_header_start.len() - input.len());
+
+        let header = Header {
+            file_type: FileType::Bitmap,
+            file_size,
+            image_data_start,
+            image_size: dib_header.image_size,
+            image_data_len: dib_header.image_data_len,
+            bpp: dib_header.bpp,
+            channel_masks: dib_header.channel_masks,
+            row_order: dib_header.row_order,
+            header_type: dib_header.header_type,
+            compression_method: dib_header.compression,
+            resolution: dib_header.resolution,
+            color_planes: dib_header.color_planes,
+            colors_used: dib_header.colors_used,
+            colors_important: dib_header.colors_important,
+            color_space: dib_header.color_space,
+            rendering_intent: dib_header.rendering_intent,
+        };
+        header.check_size()?;
+
+        Ok((input, (header, color_table, icc_profile)))
+    }
+
+    /// Returns the row length in bytes.
+    ///
+    /// Each row in a BMP file is a multiple of 4 bytes long. `None` is returned if the length
+    /// doesn't fit into a `usize`.
+    pub(crate) fn bytes_per_row(&self) -> Option<usize> {
+        let bits_per_row =
+            (self.image_size.width as usize).checked_mul(usize::from(self.bpp.bits()))?;
+
+        Some(bits_per_row.div_ceil(32) * (32 / 8))
+    }
+
+    /// Returns the length of the uncompressed image data in bytes.
+    ///
+    /// `None` is returned if the length doesn't fit into a `usize`.
+    pub(crate) fn uncompressed_data_len(&self) -> Option<usize> {
+        self.bytes_per_row()?
+            .checked_mul(self.image_size.height as usize)
+    }
+
+    /// Checks that all pixel coordinates fit into an `i32` and that all pixels and all bits in
+    /// the image data can be addressed by a `usize`.
+    fn check_size(&self) -> Result<(), ParseError> {
+        let max_dimension = i32::MAX as u32;
+        if self.image_size.width > max_dimension || self.image_size.height > max_dimension {
+            return Err(ParseError::ImageTooLarge);
+        }
+
+        let pixel_count =
+            (self.image_size.width as usize).checked_mul(self.image_size.height as usize);
+        let data_bits = self
+            .uncompressed_data_len()
+            .and_then(|len| len.checked_mul(8));
+
+        if pixel_count.is_some() && data_bits.is_some() {
+            Ok(())
+        } else {
+            Err(ParseError::ImageTooLarge)
+        }
+    }
+}
+
+/// Size of the header of an entry in an OS/2 bitmap array.
+pub(crate) const BITMAP_ARRAY_HEADER_SIZE: usize = 14;
+
+/// File type.
+///
+/// The file type is determined by the signature at the start of the file header. Icons and
+/// pointers are only used by OS/2.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum FileType {
+    /// Bitmap (`BM`).
+    ///
+    /// Images without a file header also use this file type.
+    Bitmap,
+    /// Color icon (`CI`).
+    ColorIcon,
+    /// Color pointer (`CP`).
+    ColorPointer,
+    /// Icon (`IC`).
+    Icon,
+    /// Pointer (`PT`).
+    Pointer,
+}
+
+impl FileType {
+    fn new(signature: [u8; 2]) -> Result<Self, ParseError> {
+        Ok(match &signature {
+            b"BM" => Self::Bitmap,
+            b"CI" => Self::ColorIcon,
+            b"CP" => Self::ColorPointer,
+            b"IC" => Self::Icon,
+            b"PT" => Self::Pointer,
+            _ => return Err(ParseError::InvalidFileSignature),
+        })
     }
 }
 
 /// Masks for the color channels.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ChannelMasks {
     /// Red channel mask.
     pub red: u32,
@@ -176,6 +419,7 @@ impl ChannelMasks {
     };
 
     /// Rgb565 color masks.
+    #[allow(clippy::unusual_byte_groupings)]
     pub const RGB565: Self = Self {
         red: 0b11111_000000_00000,
         green: 0b00000_111111_00000,
@@ -192,22 +436,109 @@ impl ChannelMasks {
     };
 }
 
+/// Compression method.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum CompressionMethod {
+    /// Uncompressed image data (`BI_RGB`).
     Rgb,
+    /// 8 BPP run-length encoded image data (`BI_RLE8`).
+    Rle8,
+    /// 4 BPP run-length encoded image data (`BI_RLE4`).
+    Rle4,
+    /// Uncompressed image data with channel masks (`BI_BITFIELDS`).
     Bitfields,
+    /// The image data is a JPEG stream (`BI_JPEG`).
+    Jpeg,
+    /// The image data is a PNG stream (`BI_PNG`).
+    Png,
+    /// Modified Huffman 1D compression (`BCA_HUFFMAN1D`).
+    ///
+    /// This compression method is only used by 1 BPP images with an OS/2 2.x header.
+    Huffman1d,
+    /// Uncompressed CMYK image data (`BI_CMYK`).
+    ///
+    /// The pixels of 32 BPP images and the color table entries use CMYK colors, which are
+    /// converted to RGB when the image is read. The conversion doesn't use color management and
+    /// only approximates the printed colors.
+    Cmyk,
+    /// 8 BPP run-length encoded image data with a CMYK color table (`BI_CMYKRLE8`).
+    CmykRle8,
+    /// 4 BPP run-length encoded image data with a CMYK color table (`BI_CMYKRLE4`).
+    CmykRle4,
 }
 
 impl CompressionMethod {
-    fn new(value: u32) -> Result<Self, ParseError> {
-        Ok(match value {
-            0 => Self::Rgb,
-            3 => Self::Bitfields,
+    fn new(value: u32, header_type: HeaderType) -> Result<Self, ParseError> {
+        Ok(match (value, header_type) {
+            (0, _) => Self::Rgb,
+            (1, _) => Self::Rle8,
+            (2, _) => Self::Rle4,
+            // OS/2 2.x headers use different values for all compression methods after `BI_RLE4`.
+            (3, HeaderType::Os2V2) => Self::Huffman1d,
+            (_, HeaderType::Os2V2) => {
+                return Err(ParseError::UnsupportedCompressionMethod(value));
+            }
+            (3, _) => Self::Bitfields,
+            (4, _) => Self::Jpeg,
+            (5, _) => Self::Png,
+            (11, _) => Self::Cmyk,
+            (12, _) => Self::CmykRle8,
+            (13, _) => Self::CmykRle4,
             _ => return Err(ParseError::UnsupportedCompressionMethod(value)),
         })
     }
 
-    fn parse(input: &[u8]) -> Result<(&[u8], Self), ParseError> {
-        le_u32(input).and_then(|(input, value)| Ok((input, Self::new(value)?)))
+    /// Returns `true` if the pixels or the color table entries use CMYK colors.
+    pub(crate) fn is_cmyk(self) -> bool {
+        matches!(self, Self::Cmyk | Self::CmykRle8 | Self::CmykRle4)
+    }
+
+    /// Returns `true` if the image data is an embedded JPEG or PNG stream.
+    pub(crate) fn is_embedded_stream(self) -> bool {
+        matches!(self, Self::Jpeg | Self::Png)
     }
 }
+
+/// JPEG or PNG stream which is embedded in a BMP file.
+///
+/// See the [`RawBmp::embedded_stream`] method documentation for more information.
+///
+/// [`RawBmp::embedded_stream`]: struct.RawBmp.html#method.embedded_stream
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EmbeddedStream<'a> {
+    /// JPEG stream.
+    Jpeg(&'a [u8]),
+    /// PNG stream.
+    Png(&'a [u8]),
+}
+
+/// Physical resolution of an image.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Resolution {
+    /// Horizontal resolution in pixels per meter.
+    pub pixels_per_meter_x: u32,
+    /// Vertical resolution in pixels per meter.
+    pub pixels_per_meter_y: u32,
+}
+
+impl Resolution {
+    /// Returns the horizontal and vertical resolution in dots per inch.
+    ///
+    /// The values are rounded to the nearest integer.
+    pub fn dpi(&self) -> (u32, u32) {
+        (
+            pixels_per_meter_to_dpi(self.pixels_per_meter_x),
+            pixels_per_meter_to_dpi(self.pixels_per_meter_y),
+        )
+    }
+}
+
+fn pixels_per_meter_to_dpi(value: u32) -> u32 {
+    // 1 inch = 0.0254 m
+    ((u64::from(value) * 254 + 5000) / 10000) as u32
+}
@@ -0,0 +1,488 @@
+use core::cmp::Reverse;
+
+use super::embedded_graphics_core::{
+    pixelcolor::{Gray8, Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+    header::{Bpp, CompressionMethod, RowOrder},
+    parser::{le_u16, le_u32, take_slice},
+    Bmp, DynamicBmp, Limits, ParseError, RawBmp,
+};
+
+/// Size of the ICO file header.
+const HEADER_SIZE: usize = 6;
+
+/// Size of an entry in the ICO directory.
+const DIRECTORY_ENTRY_SIZE: usize = 16;
+
+/// Signature of PNG images.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Windows icon or cursor file.
+///
+/// ICO and CUR files contain a directory of images, which are usually different versions of the
+/// same icon with different sizes and bit depths. Each entry is stored as a packed DIB, which
+/// contains the color image followed by a 1 BPP AND mask which defines the transparent pixels.
+/// 32 BPP entries use the alpha channel instead of the AND mask, unless all alpha values are zero.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb888, prelude::*};
+/// use tinybmp::Ico;
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb888> = MockDisplay::default();
+///
+/// let ico = Ico::from_slice(include_bytes!("../tests/icon.ico")).unwrap();
+///
+/// // Select the 4x4px version of the icon.
+/// let entry = ico.best_match(Size::new(4, 4)).unwrap();
+/// assert_eq!(entry.size(), Size::new(4, 4));
+///
+/// // Draw the icon. Pixels which are marked as transparent in the AND mask are skipped.
+/// let icon = entry.dynamic_image::<Rgb888>().unwrap();
+/// Image::new(&icon, Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ico<'a> {
+    data: &'a [u8],
+    ico_type: IcoType,
+    len: u16,
+    limits: Limits,
+}
+
+impl<'a> Ico<'a> {
+    /// Creates an ICO object from a byte slice.
+    ///
+    /// The directory entries are checked while the file is parsed, but the images are only parsed
+    /// when they are accessed.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_slice_with_limits(bytes, Limits::UNLIMITED)
+    }
+
+    /// Creates an ICO object from a byte slice and checks that the images don't exceed the given
+    /// limits.
+    ///
+    /// The limits are checked for each image when it is parsed. The height of an image doesn't
+    /// include the AND mask. See [`Limits`] for more information.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        let (input, reserved) = le_u16(bytes)?;
+        let (input, ico_type) = le_u16(input)?;
+        let (input, len) = le_u16(input)?;
+
+        let ico_type = match (reserved, ico_type) {
+            (0, 1) => IcoType::Icon,
+            (0, 2) => IcoType::Cursor,
+            _ => return Err(ParseError::InvalidFileSignature),
+        };
+
+        let (_, directory) = take_slice(input, usize::from(len) * DIRECTORY_ENTRY_SIZE)?;
+
+        let ico = Self {
+            data: bytes,
+            ico_type,
+            len,
+            limits,
+        };
+
+        // Check that all entries are located inside the file.
+        for entry in directory.chunks_exact(DIRECTORY_ENTRY_SIZE) {
+            ico.parse_entry(entry)?;
+        }
+
+        Ok(ico)
+    }
+
+    /// Returns the type of this file.
+    pub fn ico_type(&self) -> IcoType {
+        self.ico_type
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        usize::from(self.len)
+    }
+
+    /// Returns `true` if the file doesn't contain any entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the entry with the given index.
+    pub fn entry(&self, index: usize) -> Option<IcoEntry<'a>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let start = HEADER_SIZE + index * DIRECTORY_ENTRY_SIZE;
+        let entry = &self.data[start..start + DIRECTORY_ENTRY_SIZE];
+
+        self.parse_entry(entry).ok()
+    }
+
+    /// Returns an iterator over all entries.
+    pub fn entries(&self) -> IcoEntries<'a> {
+        IcoEntries {
+            ico: *self,
+            index: 0,
+        }
+    }
+
+    /// Returns the entry which is the best match for the requested size.
+    ///
+    /// Entries which have the exact size are preferred, followed by the smallest entry which is
+    /// larger than the requested size, followed by the largest smaller entry. If multiple entries
+    /// have the same size the entry with the highest bit depth is returned.
+    pub fn best_match(&self, size: Size) -> Option<IcoEntry<'a>> {
+        self.entries().min_by_key(|entry| {
+            let entry_size = entry.size();
+
+            let is_smaller = entry_size.width < size.width || entry_size.height < size.height;
+            let difference =
+                entry_size.width.abs_diff(size.width) + entry_size.height.abs_diff(size.height);
+
+            (is_smaller, difference, Reverse(entry.bpp()))
+        })
+    }
+
+    fn parse_entry(&self, entry: &[u8]) -> Result<IcoEntry<'a>, ParseError> {
+        let width = entry[0];
+        let height = entry[1];
+        let (_, field_1) = le_u16(&entry[4..])?;
+        let (_, field_2) = le_u16(&entry[6..])?;
+        let (_, data_len) = le_u32(&entry[8..])?;
+        let (_, data_offset) = le_u32(&entry[12..])?;
+
+        let data = self
+            .data
+            .get(data_offset as usize..)
+            .and_then(|data| data.get(..data_len as usize))
+            .ok_or(ParseError::UnexpectedEndOfFile)?;
+
+        // A width or height of 0 is used for 256px.
+        let size = Size::new(
+            if width == 0 { 256 } else { width.into() },
+            if height == 0 { 256 } else { height.into() },
+        );
+
+        // The color planes and BPP fields in the directory entries of CUR files are used to
+        // store the hotspot coordinates.
+        let (hotspot, bpp) = match self.ico_type {
+            IcoType::Icon => (None, field_2),
+            IcoType::Cursor => (Some(Point::new(field_1.into(), field_2.into())), 0),
+        };
+
+        // Use the BPP value from the DIB header if it isn't set in the directory.
+        let bpp = if bpp == 0 && !data.starts_with(PNG_SIGNATURE) {
+            data.get(14..)
+                .and_then(|data| le_u16(data).ok())
+                .map(|(_, bpp)| bpp)
+                .unwrap_or_default()
+        } else {
+            bpp
+        };
+
+        Ok(IcoEntry {
+            size,
+            bpp,
+            hotspot,
+            data,
+            limits: self.limits,
+        })
+    }
+}
+
+/// Type of an ICO file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IcoType {
+    /// Icon (`.ico`).
+    Icon,
+    /// Cursor (`.cur`).
+    Cursor,
+}
+
+/// Iterator over the entries in an ICO file.
+///
+/// See the [`Ico::entries`] method documentation for more information.
+///
+/// [`Ico::entries`]: struct.Ico.html#method.entries
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IcoEntries<'a> {
+    ico: Ico<'a>,
+    index: usize,
+}
+
+impl<'a> Iterator for IcoEntries<'a> {
+    type Item = IcoEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.ico.entry(self.index)?;
+        self.index += 1;
+
+        Some(entry)
+    }
+}
+
+/// Entry in an ICO file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct IcoEntry<'a> {
+    size: Size,
+    bpp: u16,
+    hotspot: Option<Point>,
+    data: &'a [u8],
+    limits: Limits,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for IcoEntry<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "IcoEntry {{ size: Size {{ width: {=u32}, height: {=u32} }}, bpp: {=u16}, hotspot: {}, data: {=[u8]}, limits: {} }}",
+            self.size.width,
+            self.size.height,
+            self.bpp,
+            self.hotspot.map(|p| (p.x, p.y)),
+            self.data,
+            self.limits,
+        )
+    }
+}
+
+impl<'a> IcoEntry<'a> {
+    /// Returns the size of this entry in pixels.
+    ///
+    /// The size is read from the ICO directory.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the number of bits per pixel.
+    ///
+    /// The value is read from the ICO directory or, if it isn't set in the directory, from the DIB
+    /// header. `0` is returned if the bit depth is unknown.
+    pub fn bpp(&self) -> u16 {
+        self.bpp
+    }
+
+    /// Returns the hotspot of a cursor.
+    ///
+    /// `None` is returned for entries in ICO files.
+    pub fn hotspot(&self) -> Option<Point> {
+        self.hotspot
+    }
+
+    /// Returns the raw data of this entry.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns `true` if this entry contains a PNG image.
+    ///
+    /// PNG images aren't supported by tinybmp, but the image data can be accessed by using
+    /// [`data`] and decoded by another library.
+    ///
+    /// [`data`]: #method.data
+    pub fn is_png(&self) -> bool {
+        self.data.starts_with(PNG_SIGNATURE)
+    }
+
+    /// Returns the color image of this entry without the AND mask.
+    pub fn as_raw(&self) -> Result<RawBmp<'a>, ParseError> {
+        self.parse().map(|(raw_bmp, _)| raw_bmp)
+    }
+
+    /// Returns a drawable image of this entry.
+    ///
+    /// The color type must match the bit depth of the image, see [`Bmp::from_slice`] for more
+    /// information.
+    ///
+    /// [`Bmp::from_slice`]: struct.Bmp.html#method.from_slice
+    pub fn image<C>(&self) -> Result<IcoImage<'a, Bmp<'a, C>>, ParseError>
+    where
+        C: PixelColor + From<<C as PixelColor>::Raw>,
+    {
+        let (raw_bmp, and_mask) = self.parse()?;
+
+        Ok(IcoImage::new(Bmp::from_raw(raw_bmp)?, raw_bmp, and_mask))
+    }
+
+    /// Returns a drawable image of this entry with a dynamic color type.
+    pub fn dynamic_image<C>(&self) -> Result<IcoImage<'a, DynamicBmp<'a, C>>, ParseError>
+    where
+        C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888> + From<Gray8>,
+    {
+        let (raw_bmp, and_mask) = self.parse()?;
+
+        Ok(IcoImage::new(
+            DynamicBmp::from_raw(raw_bmp)?,
+            raw_bmp,
+            and_mask,
+        ))
+    }
+
+    fn parse(&self) -> Result<(RawBmp<'a>, &'a [u8]), ParseError> {
+        if self.is_png() {
+            return Err(ParseError::UnsupportedIcoEntry);
+        }
+
+        let (raw_bmp, and_mask) = RawBmp::from_dib_slice(self.data)?.split_ico_mask();
+
+        // The limits are checked after the AND mask was removed from the image.
+        self.limits.check(raw_bmp.header(), raw_bmp.color_table())?;
+
+        Ok((raw_bmp, and_mask))
+    }
+}
+
+/// Image in an ICO file.
+///
+/// Pixels which are set in the AND mask of the image are treated as transparent. For 32 BPP
+/// images with an alpha channel, pixels with an alpha value below 50% are treated as transparent
+/// and the AND mask is ignored. See the [`Ico`] documentation for more information.
+///
+/// [`Ico`]: struct.Ico.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IcoImage<'a, T> {
+    image: T,
+    raw_bmp: RawBmp<'a>,
+    and_mask: &'a [u8],
+    alpha_mask: u32,
+}
+
+impl<'a, T> IcoImage<'a, T> {
+    fn new(image: T, raw_bmp: RawBmp<'a>, and_mask: &'a [u8]) -> Self {
+        Self {
+            image,
+            raw_bmp,
+            and_mask,
+            alpha_mask: alpha_mask(&raw_bmp),
+        }
+    }
+
+    /// Returns a reference to the color image.
+    ///
+    /// Neither the AND mask nor the alpha channel is applied to the returned image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+/// Returns the mask of the alpha channel which is used for transparency.
+///
+/// `0` is returned if the image doesn't have an alpha channel. Many encoders set all alpha values
+/// to zero in 32 BPP images without transparency, in which case the AND mask is used instead.
+fn alpha_mask(raw_bmp: &RawBmp<'_>) -> u32 {
+    let header = raw_bmp.header();
+    if header.bpp != Bpp::Bits32 {
+        return 0;
+    }
+
+    let mask = match (header.compression_method, header.channel_masks) {
+        (CompressionMethod::Rgb, _) => 0xFF00_0000,
+        (CompressionMethod::Bitfields, Some(masks)) => masks.alpha,
+        _ => 0,
+    };
+
+    if mask != 0 && raw_bmp.pixels().any(|pixel| pixel.color & mask != 0) {
+        mask
+    } else {
+        0
+    }
+}
+
+impl<T> IcoImage<'_, T>
+where
+    T: OriginDimensions,
+{
+    /// Returns `true` if the pixel is transparent.
+    ///
+    /// Pixels outside the image and pixels which aren't included in a truncated mask are treated
+    /// as opaque.
+    fn is_masked(&self, p: Point) -> bool {
+        let size = self.image.size();
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return false;
+        }
+
+        if self.alpha_mask != 0 {
+            let shift = self.alpha_mask.trailing_zeros();
+            let max = self.alpha_mask >> shift;
+            let alpha = (self.raw_bmp.pixel(p).unwrap_or_default() & self.alpha_mask) >> shift;
+
+            // Use a threshold of 50% to convert the alpha value into a binary mask.
+            return u64::from(alpha) * 2 < u64::from(max);
+        }
+
+        let (x, y) = (p.x as usize, p.y as u32);
+        let row = match self.raw_bmp.header().row_order {
+            RowOrder::BottomUp => size.height - 1 - y,
+            RowOrder::TopDown => y,
+        };
+
+        // Each row in the mask is padded to a multiple of 4 bytes.
+        let bytes_per_row = (size.width as usize).div_ceil(32) * 4;
+        let index = row as usize * bytes_per_row + x / 8;
+
+        self.and_mask
+            .get(index)
+            .is_some_and(|byte| byte & (0x80 >> (x % 8)) != 0)
+    }
+}
+
+impl<T> OriginDimensions for IcoImage<'_, T>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<T> GetPixel for IcoImage<'_, T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        if self.is_masked(p) {
+            None
+        } else {
+            self.image.pixel(p)
+        }
+    }
+}
+
+impl<T> ImageDrawable for IcoImage<'_, T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        draw_opaque_spans(target, self.size(), |p| self.pixel(p))
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
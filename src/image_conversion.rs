@@ -0,0 +1,86 @@
+//! Conversions between tinybmp and the `image` crate.
+
+use super::embedded_graphics_core::{
+    pixelcolor::{Gray8, Rgb555, Rgb565, Rgb888},
+    prelude::*,
+};
+use image::{DynamicImage, GrayImage, Rgb, RgbImage, Rgba, RgbaImage};
+
+use super::{dynamic_bmp::ColorType, DynamicBmp, ParseError, RawBmp};
+
+impl RawBmp<'_> {
+    /// Converts this image into an `image::RgbaImage`.
+    ///
+    /// The image data is decoded in the same way as by [`DynamicBmp`]. Transparent pixels are
+    /// converted to fully transparent black pixels, all other pixels are opaque.
+    ///
+    /// An error is returned if the image format isn't supported by [`DynamicBmp`].
+    ///
+    /// [`DynamicBmp`]: struct.DynamicBmp.html
+    pub fn to_rgba_image(&self) -> Result<RgbaImage, ParseError> {
+        Ok(DynamicBmp::<Rgb888>::from_raw(*self)?.to_rgba_image())
+    }
+
+    /// Converts this image into an `image::DynamicImage`.
+    ///
+    /// Grayscale images are converted into `DynamicImage::ImageLuma8` and all other images are
+    /// converted into `DynamicImage::ImageRgb8`.
+    ///
+    /// An error is returned if the image format isn't supported by [`DynamicBmp`].
+    ///
+    /// [`DynamicBmp`]: struct.DynamicBmp.html
+    pub fn to_dynamic_image(&self) -> Result<DynamicImage, ParseError> {
+        Ok(DynamicBmp::<Rgb888>::from_raw(*self)?.to_dynamic_image())
+    }
+}
+
+impl<C> DynamicBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888> + From<Gray8>,
+{
+    /// Converts this image into an `image::RgbaImage`.
+    ///
+    /// The image data is decoded in the same way as it's decoded while the image is drawn.
+    /// Transparent pixels are converted to fully transparent black pixels, all other pixels are
+    /// opaque.
+    pub fn to_rgba_image(&self) -> RgbaImage {
+        let bmp = self.with_color_type::<Rgb888>();
+        let size = self.size();
+
+        RgbaImage::from_fn(size.width, size.height, |x, y| {
+            bmp.pixel(Point::new(x as i32, y as i32))
+                .map(|c| Rgba([c.r(), c.g(), c.b(), 255]))
+                .unwrap_or(Rgba([0, 0, 0, 0]))
+        })
+    }
+
+    /// Converts this image into an `image::DynamicImage`.
+    ///
+    /// Grayscale images are converted into `DynamicImage::ImageLuma8` and all other images are
+    /// converted into `DynamicImage::ImageRgb8`.
+    pub fn to_dynamic_image(&self) -> DynamicImage {
+        let size = self.size();
+
+        if matches!(self.color_type(), ColorType::Gray8 | ColorType::Gray16) {
+            let bmp = self.with_color_type::<Gray8>();
+
+            DynamicImage::ImageLuma8(GrayImage::from_fn(size.width, size.height, |x, y| {
+                let color = bmp
+                    .pixel(Point::new(x as i32, y as i32))
+                    .unwrap_or(Gray8::BLACK);
+
+                image::Luma([color.luma()])
+            }))
+        } else {
+            let bmp = self.with_color_type::<Rgb888>();
+
+            DynamicImage::ImageRgb8(RgbImage::from_fn(size.width, size.height, |x, y| {
+                let color = bmp
+                    .pixel(Point::new(x as i32, y as i32))
+                    .unwrap_or(Rgb888::BLACK);
+
+                Rgb([color.r(), color.g(), color.b()])
+            }))
+        }
+    }
+}
@@ -67,24 +67,21 @@
 //!
 //! ```rust
 //! use embedded_graphics::prelude::*;
-//! use tinybmp::{RawBmp, Bpp, Header, RawPixel, RowOrder};
+//! use tinybmp::{Bpp, CompressionMethod, FileType, HeaderType, RawBmp, RawPixel, RowOrder};
 //!
 //! let bmp = RawBmp::from_slice(include_bytes!("../tests/chessboard-8px-24bit.bmp"))
 //!     .expect("Failed to parse BMP image");
 //!
 //! // Read the BMP header
-//! assert_eq!(
-//!     bmp.header(),
-//!     &Header {
-//!         file_size: 314,
-//!         image_data_start: 122,
-//!         bpp: Bpp::Bits24,
-//!         image_size: Size::new(8, 8),
-//!         image_data_len: 192,
-//!         channel_masks: None,
-//!         row_order: RowOrder::BottomUp,
-//!     }
-//! );
+//! let header = bmp.header();
+//! assert_eq!(header.file_type, FileType::Bitmap);
+//! assert_eq!(header.file_size, 314);
+//! assert_eq!(header.image_data_start, 122);
+//! assert_eq!(header.bpp, Bpp::Bits24);
+//! assert_eq!(header.image_size, Size::new(8, 8));
+//! assert_eq!(header.row_order, RowOrder::BottomUp);
+//! assert_eq!(header.header_type, HeaderType::V4);
+//! assert_eq!(header.compression_method, CompressionMethod::Rgb);
 //!
 //! // Check that raw image data slice is the correct length (according to parsed header)
 //! assert_eq!(bmp.image_data().len(), bmp.header().image_data_len as usize);
@@ -96,6 +93,31 @@
 //! assert_eq!(pixels.len(), 8 * 8);
 //! ```
 //!
+//! # Cargo features
+//!
+//! `tinybmp` only depends on `embedded-graphics-core` and supports the embedded-graphics 0.7 and
+//! 0.8 releases. The API for each embedded-graphics version is enabled by one of these features:
+//!
+//! * `embedded-graphics-0_7` (enabled by default): the API at the crate root uses the types from
+//!   embedded-graphics 0.7.
+//! * `embedded-graphics-0_8`: the `v0_8` module contains the same API, but uses the types from
+//!   embedded-graphics 0.8.
+//!
+//! Both features can be enabled at the same time, for example if different crates in the
+//! dependency graph use different embedded-graphics versions. Items which don't depend on
+//! embedded-graphics, like `ParseError`, are shared by both APIs.
+//!
+//! The optional `defmt` feature implements [`defmt::Format`] for all public types. With
+//! embedded-graphics 0.8 this feature also enables the `defmt` feature of `embedded-graphics-core`.
+//!
+//! The optional `image` feature adds conversions to the types of the [`image`] crate. This
+//! feature requires `std`.
+//!
+//! ```toml
+//! [dependencies]
+//! tinybmp = { version = "0.3.3", default-features = false, features = ["embedded-graphics-0_8"] }
+//! ```
+//!
 //! [`embedded-graphics`]: https://crates.io/crates/embedded-graphics
 //! [`Header`]: ./header/struct.Header.html
 //! [`Bmp`]: ./struct.Bmp.html
@@ -105,121 +127,124 @@
 //! [`from_slice`]: ./struct.RawBmp.html#method.from_slice
 //! [`pixels`]: ./struct.RawBmp.html#method.pixels
 //! [`image_data`]: ./struct.RawBmp.html#method.image_data
+//! [`image`]: https://crates.io/crates/image
+//! [`defmt::Format`]: https://docs.rs/defmt/latest/defmt/trait.Format.html
 
 #![no_std]
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 
-use core::marker::PhantomData;
-
-use embedded_graphics::{prelude::*, primitives::Rectangle};
-
-mod color_table;
-mod dynamic_bmp;
-mod header;
-mod parser;
-mod pixels;
-mod raw_bmp;
-mod raw_pixels;
-
-pub use crate::{
-    dynamic_bmp::DynamicBmp,
-    header::{Bpp, ChannelMasks, Header, RowOrder},
-    pixels::Pixels,
-    raw_bmp::RawBmp,
-    raw_pixels::{RawPixel, RawPixels},
-};
-
-/// A BMP-format bitmap
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct Bmp<'a, C> {
-    raw_bmp: RawBmp<'a>,
-    color_type: PhantomData<C>,
-}
+#[cfg(feature = "image")]
+extern crate std;
 
-impl<'a, C> Bmp<'a, C>
-where
-    C: PixelColor,
-{
-    /// Creates a bitmap object from a byte slice.
-    ///
-    /// The created object keeps a shared reference to the input and does not dynamically allocate
-    /// memory.
-    ///
-    /// The color type must be explicitly specified when this method is called, for example by
-    /// using the turbofish syntax. An error is returned if the bit depth of the specified color
-    /// type doesn't match the bit depth of the BMP file.
-    pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
-        let raw_bmp = RawBmp::from_slice(bytes)?;
-
-        if C::Raw::BITS_PER_PIXEL != usize::from(raw_bmp.color_bpp().bits()) {
-            if raw_bmp.color_bpp() == Bpp::Bits32 && C::Raw::BITS_PER_PIXEL == 24 {
-                // Allow 24BPP color types for 32BPP images to support RGB888 BMP files with
-                // 4 bytes per pixel.
-                // This check could be improved by using the bit masks available in BMP headers
-                // with version >= 4, but we don't currently parse this information.
-            } else if (raw_bmp.color_bpp() == Bpp::Bits1 || raw_bmp.color_bpp() == Bpp::Bits8)
-                && raw_bmp.color_table().is_some()
-            {
-                // Allow 1BPP and 8BPP images with color tables to be mapped to other color types.
-            } else {
-                return Err(ParseError::MismatchedBpp(raw_bmp.color_bpp().bits()));
-            }
-        }
-
-        Ok(Self {
-            raw_bmp,
-            color_type: PhantomData,
-        })
-    }
-
-    /// Returns an iterator over the pixels in this image.
-    pub fn pixels<'b>(&'b self) -> Pixels<'b, 'a, C> {
-        Pixels::new(self.raw_bmp.pixels())
-    }
-
-    /// Returns a reference to the raw BMP image.
-    ///
-    /// The [`RawBmp`] instance can be used to access lower level information about the BMP file.
-    ///
-    /// [`RawBmp`]: struct.RawBmp.html
-    pub fn as_raw(&self) -> &RawBmp<'a> {
-        &self.raw_bmp
-    }
+#[cfg(not(any(feature = "embedded-graphics-0_7", feature = "embedded-graphics-0_8")))]
+compile_error!(
+    "At least one of the `embedded-graphics-0_7` and `embedded-graphics-0_8` features must be enabled."
+);
+
+#[cfg(feature = "image")]
+mod encode;
+
+#[cfg(feature = "image")]
+pub use crate::encode::{encode_rgb_image, EncodeError};
+
+/// Declares the modules which implement the API for one embedded-graphics version.
+///
+/// The modules use the `embedded_graphics_core` crate which is imported into the module that
+/// invokes this macro. This module must be declared with `#[path = ""]` to load the module files
+/// from `src/`. Items which don't depend on embedded-graphics, like `ParseError`, are declared at
+/// the crate root and are shared by all versions.
+#[cfg(any(feature = "embedded-graphics-0_7", feature = "embedded-graphics-0_8"))]
+macro_rules! embedded_graphics_api {
+    () => {
+        mod animation;
+        mod bitmap_array;
+        mod bmp;
+        mod cmyk;
+        mod color_table;
+        mod decoder;
+        mod dither;
+        mod draw;
+        mod dynamic_bmp;
+        mod get_pixel;
+        mod header;
+        mod huffman;
+        mod ico;
+        #[cfg(feature = "image")]
+        mod image_conversion;
+        mod limits;
+        mod palette;
+        mod parser;
+        mod pixels;
+        mod raw_bmp;
+        mod raw_pixels;
+        mod rle;
+        mod scale;
+        mod solid_runs;
+        mod tile_set;
+        mod tone_map;
+        mod transform;
+
+        pub use self::{
+            animation::{BmpAnimation, FrameDurations, StripDirection},
+            bitmap_array::{BitmapArray, BitmapArrayIter},
+            bmp::Bmp,
+            dither::{DitherColor, FloydSteinberg, OrderedDither, Threshold},
+            dynamic_bmp::{ColorType, DynamicBmp},
+            get_pixel::GetPixel,
+            header::{
+                Bpp, CalibratedRgb, ChannelMasks, CieXyz, ColorSpace, CompressionMethod,
+                EmbeddedStream, FileType, Header, HeaderType, IccProfile, RenderingIntent,
+                Resolution, RowOrder,
+            },
+            ico::{Ico, IcoEntries, IcoEntry, IcoImage, IcoType},
+            limits::Limits,
+            palette::{Palette, PaletteFn, PalettePixels, PaletteSwap},
+            pixels::Pixels,
+            raw_bmp::RawBmp,
+            raw_pixels::{RawPixel, RawPixels},
+            scale::Scaled,
+            solid_runs::SolidRuns,
+            tile_set::{Tile, TileSet},
+            transform::{Rotation, Transformed},
+        };
+        pub use crate::ParseError;
+    };
 }
 
-impl<C> ImageDrawable for Bmp<'_, C>
-where
-    C: PixelColor + From<<C as PixelColor>::Raw>,
-{
-    type Color = C;
-
-    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        self.as_raw().draw(target)
-    }
-
-    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = Self::Color>,
-    {
-        self.draw(&mut target.translated(-area.top_left).clipped(area))
-    }
+/// API for embedded-graphics 0.7, which is re-exported at the crate root.
+#[cfg(feature = "embedded-graphics-0_7")]
+#[path = ""]
+mod v0_7 {
+    use embedded_graphics_core_0_3 as embedded_graphics_core;
+
+    embedded_graphics_api!();
 }
 
-impl<C> OriginDimensions for Bmp<'_, C>
-where
-    C: PixelColor,
-{
-    fn size(&self) -> Size {
-        self.raw_bmp.size()
-    }
+#[cfg(feature = "embedded-graphics-0_7")]
+pub use crate::v0_7::*;
+
+/// API for embedded-graphics 0.8.
+///
+/// This module contains the same items as the crate root, but uses the types from
+/// embedded-graphics 0.8. It is available if the `embedded-graphics-0_8` feature is enabled.
+#[cfg(feature = "embedded-graphics-0_8")]
+#[path = ""]
+// The modules are also loaded by `v0_7` if both versions are enabled.
+#[allow(clippy::duplicate_mod)]
+pub mod v0_8 {
+    use embedded_graphics_core_0_4 as embedded_graphics_core;
+
+    embedded_graphics_api!();
+
+    #[cfg(feature = "image")]
+    pub use crate::{encode_rgb_image, EncodeError};
 }
 
 /// Parse error.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum ParseError {
     /// An error occurred while parsing the header.
     Header,
@@ -238,7 +263,8 @@ pub enum ParseError {
 
     /// Invalid file signatures.
     ///
-    /// BMP files must start with `BM`.
+    /// BMP files must start with `BM`, `BA`, `CI`, `CP`, `IC` or `PT`. ICO and CUR files must
+    /// start with a reserved `0` value, followed by the file type `1` or `2`.
     InvalidFileSignature,
 
     /// Missing color table.
@@ -251,4 +277,41 @@ pub enum ParseError {
 
     /// Unsupported header length.
     UnsupportedHeaderLength(u32),
+
+    /// The entry in an ICO or CUR file uses an unsupported image format, like PNG.
+    UnsupportedIcoEntry,
+
+    /// The image data is an embedded JPEG or PNG stream, which can't be decoded by tinybmp.
+    ///
+    /// The stream can be accessed by using [`RawBmp::embedded_stream`].
+    ///
+    /// [`RawBmp::embedded_stream`]: struct.RawBmp.html#method.embedded_stream
+    UnsupportedEmbeddedStream,
+
+    /// The image is too large to be addressed on this platform.
+    ///
+    /// This error is returned if the width or height in the header is larger than `i32::MAX` or
+    /// if the image dimensions and bit depth describe more pixels or image data than fit into a
+    /// `usize`.
+    ImageTooLarge,
+
+    /// The image width exceeds [`Limits::max_width`].
+    ///
+    /// [`Limits::max_width`]: struct.Limits.html#structfield.max_width
+    WidthLimitExceeded(u32),
+
+    /// The image height exceeds [`Limits::max_height`].
+    ///
+    /// [`Limits::max_height`]: struct.Limits.html#structfield.max_height
+    HeightLimitExceeded(u32),
+
+    /// The number of color table entries exceeds [`Limits::max_palette_entries`].
+    ///
+    /// [`Limits::max_palette_entries`]: struct.Limits.html#structfield.max_palette_entries
+    PaletteLimitExceeded(u32),
+
+    /// The length of the image data exceeds [`Limits::max_data_len`].
+    ///
+    /// [`Limits::max_data_len`]: struct.Limits.html#structfield.max_data_len
+    DataLengthLimitExceeded(usize),
 }
@@ -0,0 +1,102 @@
+use super::{color_table::ColorTable, header::Header, ParseError};
+
+/// Resource limits for parsing images.
+///
+/// tinybmp never allocates memory while an image is parsed, but the time required to draw an
+/// image depends on the dimensions which are stored in its header. Applications which load
+/// untrusted images can use limits to reject images which are larger than expected, before any
+/// pixels are read. The limits are checked by the `from_slice_with_limits` and
+/// `from_dib_slice_with_limits` constructors of [`RawBmp`], [`Bmp`] and [`DynamicBmp`] and by
+/// [`Ico::from_slice_with_limits`] and [`BitmapArray::from_slice_with_limits`], which check the
+/// limits for each image when it is accessed.
+///
+/// The default limits accept all images.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::pixelcolor::Rgb888;
+/// use tinybmp::{Bmp, Limits, ParseError};
+///
+/// let limits = Limits {
+///     max_width: 4,
+///     ..Limits::default()
+/// };
+///
+/// let result = Bmp::<Rgb888>::from_slice_with_limits(
+///     include_bytes!("../tests/chessboard-8px-24bit.bmp"),
+///     limits,
+/// );
+/// assert_eq!(result, Err(ParseError::WidthLimitExceeded(8)));
+/// ```
+///
+/// [`RawBmp`]: struct.RawBmp.html
+/// [`Bmp`]: struct.Bmp.html
+/// [`DynamicBmp`]: struct.DynamicBmp.html
+/// [`Ico::from_slice_with_limits`]: struct.Ico.html#method.from_slice_with_limits
+/// [`BitmapArray::from_slice_with_limits`]: struct.BitmapArray.html#method.from_slice_with_limits
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Limits {
+    /// Maximum image width in pixels.
+    pub max_width: u32,
+
+    /// Maximum image height in pixels.
+    pub max_height: u32,
+
+    /// Maximum number of entries in the color table.
+    pub max_palette_entries: u32,
+
+    /// Maximum length of the image data in bytes.
+    ///
+    /// The length is the larger value of the image data length in the header and the length which
+    /// is required to store the uncompressed image data.
+    pub max_data_len: usize,
+}
+
+impl Limits {
+    /// Limits which accept all images.
+    pub const UNLIMITED: Self = Self {
+        max_width: u32::MAX,
+        max_height: u32::MAX,
+        max_palette_entries: u32::MAX,
+        max_data_len: usize::MAX,
+    };
+
+    /// Checks that an image doesn't exceed the limits.
+    pub(crate) fn check(
+        &self,
+        header: &Header,
+        color_table: Option<&ColorTable<'_>>,
+    ) -> Result<(), ParseError> {
+        let size = header.image_size;
+        if size.width > self.max_width {
+            return Err(ParseError::WidthLimitExceeded(size.width));
+        }
+        if size.height > self.max_height {
+            return Err(ParseError::HeightLimitExceeded(size.height));
+        }
+
+        let palette_entries = color_table.map_or(0, |table| table.len() as u32);
+        if palette_entries > self.max_palette_entries {
+            return Err(ParseError::PaletteLimitExceeded(palette_entries));
+        }
+
+        // The uncompressed length is checked while the header is parsed.
+        let data_len = header
+            .uncompressed_data_len()
+            .unwrap_or(usize::MAX)
+            .max(header.image_data_len as usize);
+        if data_len > self.max_data_len {
+            return Err(ParseError::DataLengthLimitExceeded(data_len));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
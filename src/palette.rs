@@ -0,0 +1,214 @@
+use core::marker::PhantomData;
+
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+    raw_bmp::RawBmp,
+    raw_pixels::RawPixels,
+};
+
+/// Palette which replaces the color table of an indexed image.
+///
+/// This trait is implemented for slices and arrays of colors and for closures wrapped in a
+/// [`PaletteFn`].
+///
+/// [`PaletteFn`]: struct.PaletteFn.html
+pub trait Palette<C> {
+    /// Returns the color for the given color table index.
+    ///
+    /// `None` is returned if the palette doesn't contain an entry for `index`.
+    fn get(&self, index: u32) -> Option<C>;
+}
+
+impl<C: PixelColor> Palette<C> for [C] {
+    fn get(&self, index: u32) -> Option<C> {
+        <[C]>::get(self, index as usize).copied()
+    }
+}
+
+impl<C: PixelColor, const N: usize> Palette<C> for [C; N] {
+    fn get(&self, index: u32) -> Option<C> {
+        self[..].get(index as usize).copied()
+    }
+}
+
+impl<C, P> Palette<C> for &P
+where
+    P: Palette<C> + ?Sized,
+{
+    fn get(&self, index: u32) -> Option<C> {
+        (**self).get(index)
+    }
+}
+
+/// Palette which uses a closure to map color table indices to colors.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb888, prelude::*};
+/// use tinybmp::{Bmp, PaletteFn};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb888> = MockDisplay::default();
+///
+/// let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/chessboard-8px-1bit.bmp")).unwrap();
+///
+/// // Draw the 1 BPP image in red and yellow instead of black and white.
+/// let palette = PaletteFn(|index| if index == 0 { Rgb888::RED } else { Rgb888::YELLOW });
+/// let recolored = bmp.with_palette(palette).unwrap();
+///
+/// Image::new(&recolored, Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PaletteFn<F>(pub F);
+
+impl<C, F> Palette<C> for PaletteFn<F>
+where
+    F: Fn(u32) -> C,
+{
+    fn get(&self, index: u32) -> Option<C> {
+        Some((self.0)(index))
+    }
+}
+
+/// Indexed image which is drawn with a replacement palette.
+///
+/// A `PaletteSwap` is created by calling [`Bmp::with_palette`] or [`RawBmp::with_palette`]. The
+/// palette is used instead of the color table, which is embedded in the BMP file, without copying
+/// the image data.
+///
+/// Pixels with a color table index which isn't included in the palette are treated as
+/// transparent.
+///
+/// [`Bmp::with_palette`]: struct.Bmp.html#method.with_palette
+/// [`RawBmp::with_palette`]: struct.RawBmp.html#method.with_palette
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PaletteSwap<'a, P, C> {
+    raw_bmp: RawBmp<'a>,
+    palette: P,
+    color_type: PhantomData<C>,
+}
+
+impl<'a, P, C> PaletteSwap<'a, P, C>
+where
+    P: Palette<C>,
+    C: PixelColor,
+{
+    pub(crate) fn new(raw_bmp: RawBmp<'a>, palette: P) -> Self {
+        Self {
+            raw_bmp,
+            palette,
+            color_type: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the pixels in this image.
+    ///
+    /// Pixels with a color table index which isn't included in the palette are skipped.
+    pub fn pixels<'b>(&'b self) -> PalettePixels<'b, 'a, P, C> {
+        PalettePixels {
+            raw: self.raw_bmp.pixels(),
+            palette: &self.palette,
+            color_type: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the raw BMP image.
+    pub fn as_raw(&self) -> &RawBmp<'a> {
+        &self.raw_bmp
+    }
+
+    /// Returns a reference to the palette.
+    pub fn palette(&self) -> &P {
+        &self.palette
+    }
+}
+
+impl<P, C> OriginDimensions for PaletteSwap<'_, P, C> {
+    fn size(&self) -> Size {
+        self.raw_bmp.size()
+    }
+}
+
+impl<P, C> GetPixel for PaletteSwap<'_, P, C>
+where
+    P: Palette<C>,
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<C> {
+        self.raw_bmp
+            .pixel(p)
+            .and_then(|index| self.palette.get(index))
+    }
+
+    fn with_row_major_pixels<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut dyn FnMut(Point) -> Option<C>) -> R,
+    {
+        let mut pixel = self.raw_bmp.row_major_pixels();
+
+        f(&mut |p| pixel(p).and_then(|index| self.palette.get(index)))
+    }
+}
+
+impl<P, C> ImageDrawable for PaletteSwap<'_, P, C>
+where
+    P: Palette<C>,
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut pixel = self.raw_bmp.row_major_pixels();
+
+        draw_opaque_spans(target, self.size(), |p| {
+            pixel(p).and_then(|index| self.palette.get(index))
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
+
+/// Iterator over the pixels in an image with a replacement palette.
+///
+/// See the [`PaletteSwap::pixels`] method documentation for more information.
+///
+/// [`PaletteSwap::pixels`]: struct.PaletteSwap.html#method.pixels
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PalettePixels<'a, 'b, P, C> {
+    raw: RawPixels<'a, 'b>,
+    palette: &'a P,
+    color_type: PhantomData<C>,
+}
+
+impl<P, C> Iterator for PalettePixels<'_, '_, P, C>
+where
+    P: Palette<C>,
+    C: PixelColor,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let palette = self.palette;
+
+        self.raw
+            .by_ref()
+            .find_map(|pixel| palette.get(pixel.color).map(|c| Pixel(pixel.position, c)))
+    }
+}
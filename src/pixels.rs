@@ -1,8 +1,8 @@
 use core::marker::PhantomData;
 
-use embedded_graphics::prelude::*;
+use super::embedded_graphics_core::prelude::*;
 
-use crate::{raw_pixels::RawPixels, RawPixel};
+use super::{raw_pixels::RawPixels, RawPixel};
 
 /// Iterator over the pixels in a BMP image.
 ///
@@ -10,6 +10,7 @@ use crate::{raw_pixels::RawPixels, RawPixel};
 ///
 /// [`pixels`]: struct.Bmp.html#method.pixels
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Pixels<'a, 'b, C> {
     raw: RawPixels<'a, 'b>,
     color_type: PhantomData<C>,
@@ -33,17 +34,43 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let RawPixel { position, color } = self.raw.next()?;
 
-        let color = if self.raw.raw_bmp.color_bpp().bits() <= 8 {
-            // Return an empty iterator if no color table is present.
-            let color_table = self.raw.raw_bmp.color_table()?;
+        // Return an empty iterator if no color table is present.
+        let color = self.raw.raw_bmp.raw_to_color(color)?;
 
-            color_table
-                .get(color)
-                .unwrap_or_else(|| C::Raw::from_u32(0).into()) //TODO: how should invalid color indices be handled
-        } else {
-            C::Raw::from_u32(color).into()
-        };
+        Some(Pixel(position, color))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let RawPixel { position, color } = self.raw.nth(n)?;
+        let color = self.raw.raw_bmp.raw_to_color(color)?;
+
+        Some(Pixel(position, color))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<C> DoubleEndedIterator for Pixels<'_, '_, C>
+where
+    C: PixelColor + From<<C as PixelColor>::Raw>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let RawPixel { position, color } = self.raw.next_back()?;
+        let color = self.raw.raw_bmp.raw_to_color(color)?;
+
+        Some(Pixel(position, color))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let RawPixel { position, color } = self.raw.nth_back(n)?;
+        let color = self.raw.raw_bmp.raw_to_color(color)?;
 
         Some(Pixel(position, color))
     }
 }
+
+// `Bmp` can only be created for images which include a color table if it is required, which
+// ensures that the color conversion never fails.
+impl<C> ExactSizeIterator for Pixels<'_, '_, C> where C: PixelColor + From<<C as PixelColor>::Raw> {}
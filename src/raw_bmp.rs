@@ -1,15 +1,22 @@
-use embedded_graphics::{prelude::*, primitives::Rectangle};
+use super::embedded_graphics_core::{pixelcolor::Gray8, prelude::*, primitives::Rectangle};
 
-use crate::{
+use super::{
+    cmyk::cmyk_to_rgb,
     color_table::ColorTable,
-    header::{Bpp, Header},
+    decoder::RowDecoder,
+    dynamic_bmp::ColorType,
+    header::{Bpp, ChannelMasks, CompressionMethod, EmbeddedStream, Header, IccProfile, RowOrder},
+    limits::Limits,
+    palette::{Palette, PaletteSwap},
     pixels::Pixels,
     raw_pixels::RawPixels,
+    tone_map::tone_map,
     ParseError, RawPixel,
 };
 
 /// A BMP-format bitmap.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RawBmp<'a> {
     /// Image header.
     header: Header,
@@ -19,6 +26,9 @@ pub struct RawBmp<'a> {
 
     /// Image data.
     image_data: &'a [u8],
+
+    /// ICC profile.
+    icc_profile: Option<IccProfile<'a>>,
 }
 
 impl<'a> RawBmp<'a> {
@@ -31,19 +41,95 @@ impl<'a> RawBmp<'a> {
     /// calling this method. This will disable all functions that requires a specified color type,
     /// like the [`pixels`] method.
     ///
+    /// If `bytes` contains an OS/2 bitmap array the first bitmap in the array is returned. Use
+    /// [`BitmapArray`] to access all bitmaps in the array.
+    ///
     /// [`from_slice`]: #method.from_slice
     /// [`pixels`]: #method.pixels
+    /// [`BitmapArray`]: struct.BitmapArray.html
     pub fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
-        let (_remaining, (header, color_table)) = Header::parse(bytes)?;
+        Self::from_slice_at(bytes, 0, Limits::UNLIMITED)
+    }
+
+    /// Create a bitmap object from a byte slice and check that the image doesn't exceed the given
+    /// limits.
+    ///
+    /// See [`Limits`] for more information.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        Self::from_slice_at(bytes, 0, limits)
+    }
+
+    /// Create a bitmap object from a file header which starts at the given offset.
+    ///
+    /// The image data offset in the file header is relative to the start of `bytes`.
+    pub(crate) fn from_slice_at(
+        bytes: &'a [u8],
+        offset: usize,
+        limits: Limits,
+    ) -> Result<Self, ParseError> {
+        let header_data = bytes.get(offset..).ok_or(ParseError::UnexpectedEndOfFile)?;
+        let (_remaining, (header, color_table, icc_profile)) = Header::parse(header_data)?;
+        limits.check(&header, color_table.as_ref())?;
+
+        Self::new(bytes, header, color_table, icc_profile)
+    }
+
+    /// Create a bitmap object from a byte slice which contains a BMP image without a file header.
+    ///
+    /// This constructor can be used to load packed device independent bitmaps (DIB), like the
+    /// `CF_DIB` clipboard format, which start with the DIB header instead of the 14 byte BMP file
+    /// header. The image data is expected to directly follow the DIB header, the optional
+    /// channel masks and the color table.
+    ///
+    /// Because no file header is available, the `file_size` in the returned [`Header`] is set to
+    /// the length of `bytes` and the `image_data_start` offset is relative to the start of the
+    /// DIB header.
+    ///
+    /// [`Header`]: struct.Header.html
+    pub fn from_dib_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        Self::from_dib_slice_with_limits(bytes, Limits::UNLIMITED)
+    }
+
+    /// Create a bitmap object from a byte slice which contains a BMP image without a file header
+    /// and check that the image doesn't exceed the given limits.
+    ///
+    /// See [`from_dib_slice`] and [`Limits`] for more information.
+    ///
+    /// [`from_dib_slice`]: #method.from_dib_slice
+    /// [`Limits`]: struct.Limits.html
+    pub fn from_dib_slice_with_limits(bytes: &'a [u8], limits: Limits) -> Result<Self, ParseError> {
+        let file_size = u32::try_from(bytes.len()).unwrap_or(u32::MAX);
+        let (_remaining, (header, color_table, icc_profile)) =
+            Header::parse_dib(bytes, file_size, None)?;
+        limits.check(&header, color_table.as_ref())?;
+
+        Self::new(bytes, header, color_table, icc_profile)
+    }
 
-        let image_data = &bytes
+    fn new(
+        bytes: &'a [u8],
+        header: Header,
+        color_table: Option<ColorTable<'a>>,
+        icc_profile: Option<IccProfile<'a>>,
+    ) -> Result<Self, ParseError> {
+        let mut image_data = bytes
             .get(header.image_data_start..)
             .ok_or(ParseError::UnexpectedEndOfFile)?;
 
+        // The length of embedded streams is required to be set in the header.
+        if header.compression_method.is_embedded_stream() && header.image_data_len > 0 {
+            image_data = image_data
+                .get(..header.image_data_len as usize)
+                .ok_or(ParseError::UnexpectedEndOfFile)?;
+        }
+
         Ok(Self {
             header,
             color_table,
             image_data,
+            icc_profile,
         })
     }
 
@@ -72,6 +158,41 @@ impl<'a> RawBmp<'a> {
         &self.header
     }
 
+    /// Returns the ICC profile of this image.
+    ///
+    /// An ICC profile can only be included in images with a V5 DIB header, which use the
+    /// [`ColorSpace::EmbeddedProfile`] or [`ColorSpace::LinkedProfile`] color space. The location
+    /// of the profile is checked while the image is parsed.
+    ///
+    /// [`ColorSpace::EmbeddedProfile`]: enum.ColorSpace.html#variant.EmbeddedProfile
+    /// [`ColorSpace::LinkedProfile`]: enum.ColorSpace.html#variant.LinkedProfile
+    pub fn icc_profile(&self) -> Option<IccProfile<'a>> {
+        self.icc_profile
+    }
+
+    /// Returns the JPEG or PNG stream which is embedded in this image.
+    ///
+    /// Images which use the [`CompressionMethod::Jpeg`] or [`CompressionMethod::Png`] compression
+    /// methods contain a complete JPEG or PNG stream instead of the pixel data. tinybmp can't
+    /// decode these streams, but the returned slice can be passed to a separate decoder. Trying to
+    /// create a [`Bmp`] or [`DynamicBmp`] from these images returns
+    /// [`ParseError::UnsupportedEmbeddedStream`].
+    ///
+    /// `None` is returned for all other compression methods.
+    ///
+    /// [`CompressionMethod::Jpeg`]: enum.CompressionMethod.html#variant.Jpeg
+    /// [`CompressionMethod::Png`]: enum.CompressionMethod.html#variant.Png
+    /// [`Bmp`]: struct.Bmp.html
+    /// [`DynamicBmp`]: struct.DynamicBmp.html
+    /// [`ParseError::UnsupportedEmbeddedStream`]: enum.ParseError.html#variant.UnsupportedEmbeddedStream
+    pub fn embedded_stream(&self) -> Option<EmbeddedStream<'a>> {
+        match self.header.compression_method {
+            CompressionMethod::Jpeg => Some(EmbeddedStream::Jpeg(self.image_data)),
+            CompressionMethod::Png => Some(EmbeddedStream::Png(self.image_data)),
+            _ => None,
+        }
+    }
+
     /// Returns an iterator over the raw pixels in the image.
     ///
     /// The iterator returns the raw pixel colors as `u32` values. To automatically convert the raw
@@ -82,14 +203,271 @@ impl<'a> RawBmp<'a> {
         RawPixels::new(self)
     }
 
+    /// Detects the color type of this image.
+    ///
+    /// The returned color type is used by [`DynamicBmp`] to decode the image data. An error is
+    /// returned if the image format isn't supported by [`DynamicBmp`].
+    ///
+    /// [`DynamicBmp`]: struct.DynamicBmp.html
+    pub fn detect_color_type(&self) -> Result<ColorType, ParseError> {
+        if self.embedded_stream().is_some() {
+            return Err(ParseError::UnsupportedEmbeddedStream);
+        }
+
+        Ok(match self.color_bpp() {
+            Bpp::Implied => return Err(ParseError::UnsupportedEmbeddedStream),
+            // The color table entries of 1 BPP images are converted to `Rgb888`.
+            Bpp::Bits1 => ColorType::Rgb888,
+            // 4 and 8 BPP images always use a color table. Only tables which contain shades of
+            // gray can be decoded as `Gray8`.
+            Bpp::Bits4 | Bpp::Bits8 => {
+                if self.color_table().is_some_and(ColorTable::is_grayscale) {
+                    ColorType::Gray8
+                } else {
+                    ColorType::Rgb888
+                }
+            }
+            Bpp::Bits16 => {
+                if let Some(masks) = self.header().channel_masks {
+                    match masks {
+                        ChannelMasks::RGB555 => ColorType::Rgb555,
+                        ChannelMasks::RGB565 => ColorType::Rgb565,
+                        // Grayscale images use the same mask for all color channels.
+                        ChannelMasks {
+                            red, green, blue, ..
+                        } if red != 0 && red == green && red == blue => ColorType::Gray16,
+                        _ => return Err(ParseError::UnsupportedDynamicBmpFormat),
+                    }
+                } else {
+                    // According to the GDI docs the default 16 bpp color format is Rgb555 if no
+                    // color masks are defined:
+                    // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-bitmapinfoheader
+                    ColorType::Rgb555
+                }
+            }
+            Bpp::Bits24 => ColorType::Rgb888,
+            Bpp::Bits32 => {
+                if let Some(masks) = self.header().channel_masks {
+                    if masks == ChannelMasks::RGB888 {
+                        ColorType::Rgb888
+                    } else {
+                        return Err(ParseError::UnsupportedDynamicBmpFormat);
+                    }
+                } else {
+                    ColorType::Rgb888
+                }
+            }
+            // 64 BPP images are tone mapped to `Rgb888`.
+            Bpp::Bits64 => ColorType::Rgb888,
+        })
+    }
+
+    /// Returns a view of this image which uses a replacement palette.
+    ///
+    /// The palette replaces the color table, which is embedded in the BMP file. `None` is returned
+    /// if the image doesn't use a color table.
+    pub fn with_palette<P, C>(&self, palette: P) -> Option<PaletteSwap<'a, P, C>>
+    where
+        P: Palette<C>,
+        C: PixelColor,
+    {
+        if matches!(self.color_bpp(), Bpp::Bits1 | Bpp::Bits4 | Bpp::Bits8) {
+            Some(PaletteSwap::new(*self, palette))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the raw color of the pixel at the given point.
+    ///
+    /// The raw value is returned as a `u32` regardless of the bit depth of the image. For images
+    /// that use a color table the returned value is the color table index. 64 BPP images don't
+    /// fit into a `u32` and are returned as tone mapped `Rgb888` values. 32 BPP CMYK images are
+    /// converted to `Rgb888`.
+    ///
+    /// Accessing individual pixels in Huffman 1D and RLE compressed images is slow, because all
+    /// previous rows need to be decoded.
+    ///
+    /// `None` is returned if `p` is outside the image.
+    pub fn pixel(&self, p: Point) -> Option<u32> {
+        let size = self.size();
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return None;
+        }
+
+        if let Some(mut decoder) = self.row_decoder() {
+            self.advance_decoder(&mut decoder, self.row_index(p.y as u32), p.x as u32);
+            return Some(decoder.next_pixel(self.image_data));
+        }
+
+        let bit_idx =
+            self.row_start(p.y as u32) * 8 + p.x as usize * usize::from(self.header.bpp.bits());
+
+        Some(self.read_raw_pixel(bit_idx))
+    }
+
+    /// Returns a function which returns the raw value of the pixel at the given point.
+    ///
+    /// The pixels are expected to be read in row-major order, which allows the decoder state of
+    /// compressed images to be reused instead of decoding all previous rows for each pixel.
+    /// `None` is returned for points outside the image.
+    pub(crate) fn row_major_pixels(&self) -> impl FnMut(Point) -> Option<u32> + '_ {
+        let mut pixels = self.pixels();
+
+        move |p| pixels.pixel_at(p)
+    }
+
+    /// Returns the index of the row with the given Y coordinate in the image data.
+    pub(crate) fn row_index(&self, y: u32) -> u32 {
+        if self.header.row_order == RowOrder::TopDown {
+            y
+        } else {
+            (self.header.image_size.height - 1) - y
+        }
+    }
+
+    /// Returns the byte offset of the row with the given Y coordinate in the image data.
+    pub(crate) fn row_start(&self, y: u32) -> usize {
+        self.bytes_per_row() * self.row_index(y) as usize
+    }
+
+    /// Returns a decoder for the first row in the image data of a Huffman 1D or RLE compressed
+    /// image.
+    ///
+    /// `None` is returned if the image isn't compressed.
+    pub(crate) fn row_decoder(&self) -> Option<RowDecoder> {
+        RowDecoder::new(self.header.compression_method, self.image_data)
+    }
+
+    /// Moves a decoder forward to the pixel with the given X coordinate in the given row of the
+    /// image data.
+    ///
+    /// The decoder must not be positioned after the pixel.
+    pub(crate) fn advance_decoder(&self, decoder: &mut RowDecoder, row: u32, x: u32) {
+        while decoder.row() < row {
+            decoder.next_row(self.image_data, self.header.image_size.width);
+        }
+
+        decoder.skip(self.image_data, x.saturating_sub(decoder.x()));
+    }
+
+    /// Reads the raw value of the pixel which starts at the given bit index in the image data.
+    ///
+    /// Pixels outside the image data are returned as `0`.
+    pub(crate) fn read_raw_pixel(&self, bit_idx: usize) -> u32 {
+        let byte_idx = bit_idx / 8;
+
+        let mut pixel_value = [0u8; 4];
+
+        match self.header.bpp {
+            Bpp::Implied => None,
+            Bpp::Bits1 => self.image_data.get(byte_idx).map(|byte| {
+                let mask = 0b_1000_0000 >> (bit_idx % 8);
+                pixel_value[0] = (byte & mask != 0) as u8;
+            }),
+            Bpp::Bits4 => self.image_data.get(byte_idx).map(|byte| {
+                // The first pixel is stored in the high nibble.
+                pixel_value[0] = if bit_idx % 8 < 4 {
+                    byte >> 4
+                } else {
+                    byte & 0xF
+                };
+            }),
+            Bpp::Bits8 => self
+                .image_data
+                .get(byte_idx)
+                .map(|byte| pixel_value[0] = *byte),
+            Bpp::Bits16 => self.image_data.get(byte_idx..byte_idx + 2).map(|data| {
+                pixel_value[0..2].copy_from_slice(data);
+            }),
+            Bpp::Bits24 => self.image_data.get(byte_idx..byte_idx + 3).map(|data| {
+                pixel_value[0..3].copy_from_slice(data);
+            }),
+            Bpp::Bits32 => self.image_data.get(byte_idx..byte_idx + 4).map(|data| {
+                pixel_value[0..4].copy_from_slice(data);
+            }),
+            Bpp::Bits64 => self.image_data.get(byte_idx..byte_idx + 8).map(|data| {
+                pixel_value = tone_map(data).to_le_bytes();
+            }),
+        };
+
+        let raw = u32::from_le_bytes(pixel_value);
+
+        if self.header.bpp == Bpp::Bits32
+            && self.header.compression_method == CompressionMethod::Cmyk
+        {
+            cmyk_to_rgb(raw)
+        } else {
+            raw
+        }
+    }
+
+    /// Converts a raw pixel value into a color.
+    ///
+    /// Raw values of images with a color table are looked up in the table. `None` is returned if
+    /// the color table is missing.
+    pub(crate) fn raw_to_color<C>(&self, raw: u32) -> Option<C>
+    where
+        C: PixelColor + From<<C as PixelColor>::Raw>,
+    {
+        if self.color_bpp().bits() <= 8 {
+            let color_table = self.color_table()?;
+
+            Some(
+                color_table
+                    .get(raw)
+                    .unwrap_or_else(|| C::Raw::from_u32(0).into()), //TODO: how should invalid color indices be handled
+            )
+        } else {
+            Some(C::Raw::from_u32(raw).into())
+        }
+    }
+
+    /// Converts a raw 16 BPP grayscale value into `Gray8`.
+    ///
+    /// The grayscale value is extracted by using the red channel mask.
+    pub(crate) fn gray16_to_gray8(&self, raw: u32) -> Gray8 {
+        let mask = self.header.channel_masks.unwrap_or_default().red;
+        if mask == 0 {
+            return Gray8::BLACK;
+        }
+
+        let shift = mask.trailing_zeros();
+        let bits = 32 - mask.leading_zeros() - shift;
+        let value = (raw & mask) >> shift;
+
+        let luma = if bits >= 8 {
+            value >> (bits - 8)
+        } else {
+            value * 255 / ((1 << bits) - 1)
+        };
+
+        Gray8::new(luma as u8)
+    }
+
+    /// Splits an ICO or CUR image into the color image and the AND mask.
+    ///
+    /// The image height in the DIB header of ICO and CUR images includes the AND mask, which is
+    /// stored after the color image data.
+    pub(crate) fn split_ico_mask(mut self) -> (Self, &'a [u8]) {
+        let height = self.header.image_size.height / 2;
+        self.header.image_size.height = height;
+
+        let color_data_len = (self.bytes_per_row() * height as usize).min(self.image_data.len());
+        self.header.image_data_len = color_data_len as u32;
+
+        let (color_data, and_mask) = self.image_data.split_at(color_data_len);
+        self.image_data = color_data;
+
+        (self, and_mask)
+    }
+
     /// Returns the row length in bytes.
     ///
     /// Each row in a BMP file is a multiple of 4 bytes long.
     pub(crate) fn bytes_per_row(&self) -> usize {
-        let bits_per_row =
-            self.header.image_size.width as usize * usize::from(self.header.bpp.bits());
-
-        (bits_per_row + 31) / 32 * (32 / 8)
+        // The length of the image data is checked while the header is parsed.
+        self.header.bytes_per_row().unwrap_or_default()
     }
 
     pub(crate) fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
@@ -1,37 +1,124 @@
-use embedded_graphics::prelude::*;
+use super::embedded_graphics_core::prelude::*;
 
-use crate::{
-    header::{Bpp, RowOrder},
-    raw_bmp::RawBmp,
-};
+use super::{decoder::RowDecoder, raw_bmp::RawBmp};
 
 /// Iterator over individual BMP pixels.
 ///
 /// Each pixel is returned as a `u32` regardless of the bit depth of the source image.
+/// 64 BPP pixels are returned as tone mapped `Rgb888` values and 32 BPP CMYK pixels are converted
+/// to `Rgb888`.
+///
+/// The position of each pixel can be calculated from its index, which allows this iterator to skip
+/// pixels with [`nth`] and to iterate backwards in constant time for uncompressed images. Huffman
+/// 1D and RLE compressed images need to be decoded up to the requested pixel, which is sped up by
+/// reusing the decoder states of earlier calls.
+///
+/// [`nth`]: #method.nth
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct RawPixels<'a, 'b> {
     /// Reference to original BMP image.
     pub(crate) raw_bmp: &'a RawBmp<'b>,
 
-    /// Image pixel data as a byte slice, little endian ordering.
-    pixel_data: &'b [u8],
-
     /// Current position.
     position: Point,
 
+    /// Number of remaining pixels.
+    remaining: usize,
+
     /// Start bit index for the current pixel.
     ///
     /// This is incremented by `pixel_stride` bits every iteration.
     bit_idx: usize,
+
+    /// Decoders used by `next` and `nth` for compressed images.
+    front: Decoders,
+
+    /// Decoders used by `next_back` and `nth_back` for compressed images.
+    back: Decoders,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RawPixels<'_, '_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "RawPixels {{ raw_bmp: {}, position: Point {{ x: {=i32}, y: {=i32} }}, remaining: {=usize}, bit_idx: {=usize}, front: {}, back: {} }}",
+            self.raw_bmp,
+            self.position.x,
+            self.position.y,
+            self.remaining,
+            self.bit_idx,
+            self.front,
+            self.back,
+        )
+    }
 }
 
 impl<'a, 'b> RawPixels<'a, 'b> {
     pub(crate) fn new(raw_bmp: &'a RawBmp<'b>) -> Self {
+        let size = raw_bmp.size();
+
         Self {
             raw_bmp,
-            pixel_data: raw_bmp.image_data(),
             position: Point::zero(),
+            remaining: (size.width as usize).saturating_mul(size.height as usize),
             bit_idx: 0,
+            front: Decoders::default(),
+            back: Decoders::default(),
+        }
+    }
+
+    /// Returns the raw value of the pixel at the given point and moves the iterator to the
+    /// following pixel.
+    ///
+    /// This is used to access the pixels of compressed images in row-major order without decoding
+    /// all previous rows for each pixel. `None` is returned if `p` is outside the image.
+    pub(crate) fn pixel_at(&mut self, p: Point) -> Option<u32> {
+        let size = self.raw_bmp.size();
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return None;
+        }
+
+        let index = self.position_to_index(p);
+        self.remaining = self.remaining + self.index() - index;
+        if p != self.position {
+            self.seek(index);
+        }
+
+        self.next().map(|pixel| pixel.color)
+    }
+
+    /// Returns the index of the current pixel.
+    fn index(&self) -> usize {
+        self.position_to_index(self.position)
+    }
+
+    /// Returns the index of the pixel at the given position.
+    fn position_to_index(&self, p: Point) -> usize {
+        p.y as usize * self.raw_bmp.size().width as usize + p.x as usize
+    }
+
+    /// Returns the position of the pixel with the given index.
+    fn index_to_position(&self, index: usize) -> Point {
+        let width = self.raw_bmp.size().width as usize;
+
+        Point::new((index % width) as i32, (index / width) as i32)
+    }
+
+    /// Returns the start bit index of the pixel at the given position.
+    fn bit_index(&self, p: Point) -> usize {
+        self.raw_bmp.row_start(p.y as u32) * 8
+            + p.x as usize * usize::from(self.raw_bmp.color_bpp().bits())
+    }
+
+    /// Moves the current position to the pixel with the given index.
+    fn seek(&mut self, index: usize) {
+        self.position = self.index_to_position(index);
+
+        // The start of the row is set by `next` for pixels at the start of a row.
+        if self.position.x > 0 {
+            self.bit_idx = self.bit_index(self.position);
+            self.front.seek(self.raw_bmp, self.position);
         }
     }
 }
@@ -40,22 +127,16 @@ impl Iterator for RawPixels<'_, '_> {
     type Item = RawPixel;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.position.y >= self.raw_bmp.size().height as i32 {
+        if self.remaining == 0 {
             return None;
         }
+        self.remaining -= 1;
 
         let p = self.position;
 
         if self.position.x == 0 {
-            let row_index = if self.raw_bmp.header().row_order == RowOrder::TopDown {
-                self.position.y
-            } else {
-                (self.raw_bmp.size().height as i32 - 1) - self.position.y
-            };
-
-            let row_start = self.raw_bmp.bytes_per_row() * row_index as usize;
-
-            self.bit_idx = row_start * 8;
+            self.bit_idx = self.bit_index(self.position);
+            self.front.seek(self.raw_bmp, self.position);
         }
 
         self.position.x += 1;
@@ -64,36 +145,170 @@ impl Iterator for RawPixels<'_, '_> {
             self.position.x = 0;
         }
 
-        let byte_idx = self.bit_idx / 8;
-
-        let mut pixel_value = [0u8; 4];
-
-        match self.raw_bmp.color_bpp() {
-            Bpp::Bits1 => self.pixel_data.get(byte_idx).map(|byte| {
-                let mask = 0b_1000_0000 >> self.bit_idx % 8;
-                pixel_value[0] = (byte & mask != 0) as u8;
-            }),
-            Bpp::Bits8 => self
-                .pixel_data
-                .get(byte_idx)
-                .map(|byte| pixel_value[0] = *byte),
-            Bpp::Bits16 => self.pixel_data.get(byte_idx..byte_idx + 2).map(|data| {
-                pixel_value[0..2].copy_from_slice(data);
-            }),
-            Bpp::Bits24 => self.pixel_data.get(byte_idx..byte_idx + 3).map(|data| {
-                pixel_value[0..3].copy_from_slice(data);
-            }),
-            Bpp::Bits32 => self.pixel_data.get(byte_idx..byte_idx + 4).map(|data| {
-                pixel_value[0..4].copy_from_slice(data);
-            }),
+        let pixel_value = match &mut self.front.current {
+            Some(decoder) => decoder.next_pixel(self.raw_bmp.image_data()),
+            None => self.raw_bmp.read_raw_pixel(self.bit_idx),
         };
 
         self.bit_idx += usize::from(self.raw_bmp.color_bpp().bits());
 
-        Some(RawPixel::new(p, u32::from_le_bytes(pixel_value)))
+        Some(RawPixel::new(p, pixel_value))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+
+        if n > 0 {
+            self.remaining -= n;
+            self.seek(self.index() + n);
+        }
+
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
     }
 }
 
+impl DoubleEndedIterator for RawPixels<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let p = self.index_to_position(self.index() + self.remaining);
+
+        self.back.seek(self.raw_bmp, p);
+        let pixel_value = match &mut self.back.current {
+            Some(decoder) => decoder.next_pixel(self.raw_bmp.image_data()),
+            None => self.raw_bmp.read_raw_pixel(self.bit_index(p)),
+        };
+
+        Some(RawPixel::new(p, pixel_value))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.remaining -= n;
+
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for RawPixels<'_, '_> {}
+
+/// Decoder states of a compressed image.
+///
+/// Compressed images can only be decoded in the order of the image data. To access pixels in
+/// other orders without decoding the image from the start for every pixel, the decoder is
+/// restarted from the closest earlier state.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Decoders {
+    /// Decoder for the current pixel.
+    current: Option<RowDecoder>,
+
+    /// Decoder at the start of the row of the current pixel.
+    row_start: Option<RowDecoder>,
+
+    /// Decoder at the start of an earlier row in the image data.
+    row_checkpoint: Option<RowDecoder>,
+
+    /// Decoder at an earlier pixel in the row of the current pixel.
+    pixel_checkpoint: Option<RowDecoder>,
+}
+
+impl Decoders {
+    /// Moves the current decoder to the given pixel.
+    ///
+    /// Nothing is done if the image isn't compressed.
+    fn seek(&mut self, raw_bmp: &RawBmp<'_>, p: Point) {
+        let start = match raw_bmp.row_decoder() {
+            Some(decoder) => decoder,
+            None => return,
+        };
+
+        let size = raw_bmp.size();
+        let row = raw_bmp.row_index(p.y as u32);
+        let x = p.x as u32;
+
+        if !matches!(self.row_start, Some(decoder) if decoder.row() == row) {
+            let mut decoder = closest([self.current, self.row_start, self.row_checkpoint], row, 0)
+                .unwrap_or(start);
+
+            // The rows of bottom-up images are iterated in reverse order of the image data.
+            // Keeping a checkpoint `sqrt(height)` rows before the current row limits the number of
+            // decoded rows to `O(height * sqrt(height))` instead of `O(height^2)`.
+            let checkpoint_row = row.saturating_sub(checkpoint_distance(size.height));
+            if position(&decoder) <= (checkpoint_row, 0) {
+                raw_bmp.advance_decoder(&mut decoder, checkpoint_row, 0);
+                self.row_checkpoint = Some(decoder);
+            }
+
+            raw_bmp.advance_decoder(&mut decoder, row, 0);
+            self.row_start = Some(decoder);
+        }
+
+        let mut decoder = closest(
+            [self.current, self.pixel_checkpoint, self.row_start],
+            row,
+            x,
+        )
+        .unwrap_or(start);
+
+        // Iterating backwards visits the pixels in each row in reverse order, which is handled
+        // like the rows of bottom-up images.
+        let checkpoint_x = x.saturating_sub(checkpoint_distance(size.width));
+        if position(&decoder) < (row, checkpoint_x) {
+            raw_bmp.advance_decoder(&mut decoder, row, checkpoint_x);
+            self.pixel_checkpoint = Some(decoder);
+        }
+
+        raw_bmp.advance_decoder(&mut decoder, row, x);
+        self.current = Some(decoder);
+    }
+}
+
+/// Returns the position of a decoder in the image data.
+fn position(decoder: &RowDecoder) -> (u32, u32) {
+    (decoder.row(), decoder.x())
+}
+
+/// Returns the decoder which is closest to, but not after, the given pixel in the image data.
+fn closest(decoders: [Option<RowDecoder>; 3], row: u32, x: u32) -> Option<RowDecoder> {
+    decoders
+        .into_iter()
+        .flatten()
+        .filter(|decoder| position(decoder) <= (row, x))
+        .max_by_key(position)
+}
+
+/// Returns the number of rows or pixels between a checkpoint and the current position.
+fn checkpoint_distance(length: u32) -> u32 {
+    let mut distance = 1u32;
+    while distance.saturating_mul(distance) < length {
+        distance += 1;
+    }
+
+    distance
+}
+
 /// Pixel with raw pixel color stored as a `u32`.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
 pub struct RawPixel {
@@ -104,6 +319,19 @@ pub struct RawPixel {
     pub color: u32,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for RawPixel {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "RawPixel {{ position: Point {{ x: {=i32}, y: {=i32} }}, color: {=u32} }}",
+            self.position.x,
+            self.position.y,
+            self.color,
+        )
+    }
+}
+
 impl RawPixel {
     /// Creates a new raw pixel.
     pub fn new(position: Point, color: u32) -> Self {
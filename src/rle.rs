@@ -0,0 +1,221 @@
+//! Run-length encoding (`BI_RLE8` and `BI_RLE4`) decoder.
+//!
+//! The image data is a sequence of two byte commands. A command with a non-zero first byte is an
+//! encoded run, which repeats the color index in the second byte. RLE4 runs alternate between the
+//! high and the low nibble of the second byte. A zero first byte is followed by an escape code:
+//!
+//! - `0`: end of line,
+//! - `1`: end of bitmap,
+//! - `2`: delta, followed by two bytes which move the position right and down,
+//! - `3..=255`: absolute run of uncompressed pixels, padded to a multiple of 16 bits.
+//!
+//! Pixels which are skipped by an end of line, delta or end of bitmap command and pixels after
+//! invalid data are decoded as color index `0`. Pixels past the end of a row are discarded.
+//!
+//! The CMYK RLE variants (`BI_CMYKRLE8` and `BI_CMYKRLE4`) use the same encoding.
+
+/// Decoder state.
+///
+/// The decoder reads the commands in the order in which they are stored in the image data and
+/// tracks the position of the next decoded pixel separately from the position of the next
+/// returned pixel, which allows it to fill in skipped pixels.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct RleDecoder {
+    /// Index of the current row in the image data.
+    row: u32,
+
+    /// X coordinate of the next pixel.
+    x: u32,
+
+    /// Byte index of the next command.
+    byte_idx: usize,
+
+    /// Row index of the next pixel in the current command.
+    stream_row: u32,
+
+    /// X coordinate of the next pixel in the current command.
+    stream_x: u32,
+
+    /// Remaining pixels in the current command.
+    remaining: u32,
+
+    /// Index of the next pixel in the current command.
+    run_idx: u32,
+
+    /// Color index of an encoded run.
+    value: u8,
+
+    /// Byte index of the pixel data of an absolute run.
+    ///
+    /// `None` if the current command is an encoded run.
+    absolute: Option<usize>,
+
+    /// The image uses 4 BPP RLE compression.
+    bits4: bool,
+
+    /// The end of the bitmap or invalid data was reached.
+    end: bool,
+}
+
+impl RleDecoder {
+    /// Creates a decoder for the first row in the image data.
+    pub(crate) fn new(bits4: bool) -> Self {
+        Self {
+            row: 0,
+            x: 0,
+            byte_idx: 0,
+            stream_row: 0,
+            stream_x: 0,
+            remaining: 0,
+            run_idx: 0,
+            value: 0,
+            absolute: None,
+            bits4,
+            end: false,
+        }
+    }
+
+    /// Returns the index of the current row in the image data.
+    pub(crate) fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Returns the X coordinate of the next pixel.
+    pub(crate) fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Returns the byte at the given index and sets the end flag if the data is truncated.
+    fn byte(&mut self, data: &[u8], index: usize) -> u8 {
+        data.get(index).copied().unwrap_or_else(|| {
+            self.end = true;
+            0
+        })
+    }
+
+    /// Reads the next command.
+    fn read_command(&mut self, data: &[u8]) {
+        let first = self.byte(data, self.byte_idx);
+        let second = self.byte(data, self.byte_idx + 1);
+        self.byte_idx += 2;
+
+        if self.end {
+            return;
+        }
+
+        match (first, second) {
+            (0, 0) => {
+                self.stream_row = self.stream_row.saturating_add(1);
+                self.stream_x = 0;
+            }
+            (0, 1) => self.end = true,
+            (0, 2) => {
+                let dx = self.byte(data, self.byte_idx);
+                let dy = self.byte(data, self.byte_idx + 1);
+                self.byte_idx += 2;
+
+                self.stream_x = self.stream_x.saturating_add(dx.into());
+                self.stream_row = self.stream_row.saturating_add(dy.into());
+            }
+            (0, count) => {
+                let len = if self.bits4 {
+                    usize::from(count).div_ceil(2)
+                } else {
+                    usize::from(count)
+                };
+
+                self.absolute = Some(self.byte_idx);
+                self.remaining = count.into();
+                self.run_idx = 0;
+                self.byte_idx += len + len % 2;
+            }
+            (count, value) => {
+                self.absolute = None;
+                self.value = value;
+                self.remaining = count.into();
+                self.run_idx = 0;
+            }
+        }
+    }
+
+    /// Discards decoded pixels before the next pixel and reads commands until the next decoded
+    /// pixel isn't before the next pixel.
+    fn catch_up(&mut self, data: &[u8]) {
+        loop {
+            if self.remaining == 0 {
+                if self.end {
+                    return;
+                }
+
+                self.read_command(data);
+                continue;
+            }
+
+            if (self.stream_row, self.stream_x) >= (self.row, self.x) {
+                return;
+            }
+
+            let discarded = if self.stream_row < self.row {
+                self.remaining
+            } else {
+                self.remaining.min(self.x - self.stream_x)
+            };
+
+            self.remaining -= discarded;
+            self.run_idx += discarded;
+            self.stream_x = self.stream_x.saturating_add(discarded);
+        }
+    }
+
+    /// Returns the color index of the next pixel in the current command.
+    fn run_pixel(&self, data: &[u8]) -> u32 {
+        let index = self.run_idx as usize;
+        let byte = match self.absolute {
+            Some(start) if self.bits4 => data.get(start + index / 2).copied().unwrap_or(0),
+            Some(start) => data.get(start + index).copied().unwrap_or(0),
+            None => self.value,
+        };
+
+        match (self.bits4, index % 2) {
+            (false, _) => byte.into(),
+            // The first pixel is stored in the high nibble.
+            (true, 0) => (byte >> 4).into(),
+            (true, _) => (byte & 0xF).into(),
+        }
+    }
+
+    /// Returns the raw value of the next pixel.
+    pub(crate) fn next_pixel(&mut self, data: &[u8]) -> u32 {
+        self.catch_up(data);
+
+        let value = if self.remaining > 0 && (self.stream_row, self.stream_x) == (self.row, self.x)
+        {
+            let value = self.run_pixel(data);
+            self.remaining -= 1;
+            self.run_idx += 1;
+            self.stream_x = self.stream_x.saturating_add(1);
+
+            value
+        } else {
+            0
+        };
+
+        self.x = self.x.saturating_add(1);
+
+        value
+    }
+
+    /// Skips the given number of pixels.
+    pub(crate) fn skip(&mut self, data: &[u8], count: u32) {
+        self.x = self.x.saturating_add(count);
+        self.catch_up(data);
+    }
+
+    /// Moves the decoder to the start of the next row.
+    pub(crate) fn next_row(&mut self, data: &[u8]) {
+        self.row = self.row.saturating_add(1);
+        self.x = 0;
+        self.catch_up(data);
+    }
+}
@@ -0,0 +1,131 @@
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+};
+
+/// Scaled view of an image.
+///
+/// `Scaled` uses nearest-neighbor sampling to scale an image to an arbitrary size. It can be
+/// created by calling [`Bmp::scaled`] or [`Bmp::scaled_to`] (or the identical methods on
+/// [`DynamicBmp`]), or by wrapping any other [`GetPixel`] image with [`Scaled::new`] or
+/// [`Scaled::with_size`].
+///
+/// The pixels are read directly from the BMP image data and are drawn one row at a time by using
+/// [`fill_contiguous`]. Rows which contain transparent pixels are split into multiple spans.
+///
+/// [`Bmp::scaled`]: struct.Bmp.html#method.scaled
+/// [`Bmp::scaled_to`]: struct.Bmp.html#method.scaled_to
+/// [`DynamicBmp`]: struct.DynamicBmp.html
+/// [`GetPixel`]: trait.GetPixel.html
+/// [`Scaled::new`]: #method.new
+/// [`Scaled::with_size`]: #method.with_size
+/// [`fill_contiguous`]: https://docs.rs/embedded-graphics/latest/embedded_graphics/draw_target/trait.DrawTarget.html#method.fill_contiguous
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Scaled<T> {
+    image: T,
+    size: Size,
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> defmt::Format for Scaled<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Scaled {{ image: {}, size: Size {{ width: {=u32}, height: {=u32} }} }}",
+            self.image,
+            self.size.width,
+            self.size.height,
+        )
+    }
+}
+
+impl<T> Scaled<T>
+where
+    T: OriginDimensions,
+{
+    /// Scales an image by an integer factor.
+    pub fn new(image: T, factor: u32) -> Self {
+        let size = image.size();
+        let size = Size::new(
+            size.width.saturating_mul(factor),
+            size.height.saturating_mul(factor),
+        );
+
+        Self { image, size }
+    }
+
+    /// Scales an image to the given size.
+    ///
+    /// The scale factors for the X and Y axis are independent of each other and can be smaller
+    /// than `1` to reduce the size of the image.
+    pub fn with_size(image: T, size: Size) -> Self {
+        Self { image, size }
+    }
+
+    /// Returns a reference to the unscaled image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+
+    /// Maps a point in the scaled image to the corresponding point in the source image.
+    fn source_point(&self, p: Point) -> Point {
+        let source_size = self.image.size();
+
+        Point::new(
+            source_coordinate(p.x, source_size.width, self.size.width),
+            source_coordinate(p.y, source_size.height, self.size.height),
+        )
+    }
+}
+
+/// Maps a coordinate along one axis of the scaled image to the source image.
+fn source_coordinate(value: i32, source_length: u32, scaled_length: u32) -> i32 {
+    // 64 bit arithmetic is used to prevent overflows for large images.
+    (value as u64 * u64::from(source_length) / u64::from(scaled_length)) as i32
+}
+
+impl<T> OriginDimensions for Scaled<T> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<T> GetPixel for Scaled<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        if p.x < 0 || p.y < 0 || p.x as u32 >= self.size.width || p.y as u32 >= self.size.height {
+            return None;
+        }
+
+        self.image.pixel(self.source_point(p))
+    }
+}
+
+impl<T> ImageDrawable for Scaled<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        draw_opaque_spans(target, self.size, |p| {
+            self.image.pixel(self.source_point(p))
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
@@ -0,0 +1,254 @@
+use core::iter;
+
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use super::{draw::SubImageTarget, get_pixel::GetPixel};
+
+/// View of an image which draws runs of identical pixels with `fill_solid`.
+///
+/// Display drivers which need to set the address window for each draw call can update uniform
+/// areas, which are common in UI assets, faster by using [`fill_solid`] instead of sending the
+/// same color for each pixel. `SolidRuns` detects horizontal runs of pixels with the same color
+/// and draws all runs which are at least `min_run_length` pixels long with a single call to
+/// [`fill_solid`]. The remaining pixels in each row are drawn by using [`fill_contiguous`].
+/// Transparent pixels are skipped.
+///
+/// The pixels are read only once, in row-major order, by using
+/// [`GetPixel::with_row_major_pixels`], which allows compressed images to be drawn without
+/// decoding the image data multiple times. Because the length of a span of short runs isn't known
+/// before its pixels are read, the area passed to [`fill_contiguous`] extends to the end of the
+/// row and the color iterator ends at the first long run or transparent pixel.
+///
+/// A `SolidRuns` view can be created by calling [`Bmp::solid_runs`] or
+/// [`DynamicBmp::solid_runs`], or by wrapping any other [`GetPixel`] image with
+/// [`SolidRuns::new`].
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+/// use tinybmp::Bmp;
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+///
+/// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/chessboard-8px-color-16bit.bmp"))
+///     .unwrap();
+///
+/// // Use `fill_solid` for all runs which are at least 4 pixels long.
+/// Image::new(&bmp.solid_runs(4), Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`fill_solid`]: https://docs.rs/embedded-graphics/latest/embedded_graphics/draw_target/trait.DrawTarget.html#method.fill_solid
+/// [`fill_contiguous`]: https://docs.rs/embedded-graphics/latest/embedded_graphics/draw_target/trait.DrawTarget.html#method.fill_contiguous
+/// [`Bmp::solid_runs`]: struct.Bmp.html#method.solid_runs
+/// [`DynamicBmp::solid_runs`]: struct.DynamicBmp.html#method.solid_runs
+/// [`GetPixel`]: trait.GetPixel.html
+/// [`GetPixel::with_row_major_pixels`]: trait.GetPixel.html#method.with_row_major_pixels
+/// [`SolidRuns::new`]: #method.new
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SolidRuns<T> {
+    image: T,
+    min_run_length: u32,
+}
+
+impl<T> SolidRuns<T> {
+    /// Creates a new view which uses `fill_solid` for runs with at least `min_run_length` pixels.
+    ///
+    /// A `min_run_length` of `0` or `1` draws every run with `fill_solid`.
+    pub fn new(image: T, min_run_length: u32) -> Self {
+        Self {
+            image,
+            min_run_length,
+        }
+    }
+
+    /// Returns the minimum length of runs which are drawn with `fill_solid`.
+    pub fn min_run_length(&self) -> u32 {
+        self.min_run_length
+    }
+
+    /// Returns a reference to the wrapped image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+impl<T> OriginDimensions for SolidRuns<T>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<T> GetPixel for SolidRuns<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        self.image.pixel(p)
+    }
+
+    fn with_row_major_pixels<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut dyn FnMut(Point) -> Option<Self::Color>) -> R,
+    {
+        self.image.with_row_major_pixels(f)
+    }
+}
+
+impl<T> ImageDrawable for SolidRuns<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let size = self.image.size();
+        let width = size.width as i32;
+        let min_run_length = self.min_run_length;
+
+        self.image.with_row_major_pixels(|pixel| {
+            for y in 0..size.height as i32 {
+                let mut runs = RowRuns::new(pixel, y, width);
+
+                loop {
+                    match runs.read_run(min_run_length) {
+                        // End of the row.
+                        (_, 0) => break,
+                        // Skip transparent pixels.
+                        (None, length) => runs.consume(length),
+                        (Some(color), length) if length >= min_run_length => {
+                            let (_, length) = runs.read_run(u32::MAX);
+                            target.fill_solid(
+                                &Rectangle::new(Point::new(runs.x, y), Size::new(length, 1)),
+                                color,
+                            )?;
+                            runs.consume(length);
+                        }
+                        (Some(_), _) => {
+                            let start_x = runs.x;
+
+                            let colors = iter::from_fn(|| match runs.read_run(min_run_length) {
+                                (Some(color), length) if length < min_run_length => {
+                                    runs.consume(1);
+                                    Some(color)
+                                }
+                                _ => None,
+                            });
+
+                            target.fill_contiguous(
+                                &Rectangle::new(
+                                    Point::new(start_x, y),
+                                    Size::new((width - start_x) as u32, 1),
+                                ),
+                                colors,
+                            )?;
+
+                            // Targets don't need to consume colors which are outside of their
+                            // drawing area.
+                            if runs.x == start_x {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
+
+/// Splits the pixels in a row into runs of identical pixels.
+///
+/// Each pixel is read only once. Reading a run stops at the first pixel with a different color,
+/// which is kept as the first pixel of the following run.
+struct RowRuns<'a, C> {
+    pixel: &'a mut dyn FnMut(Point) -> Option<C>,
+    y: i32,
+    width: i32,
+
+    /// X coordinate of the first pixel which hasn't been drawn yet.
+    x: i32,
+
+    /// Color and length of the known part of the run which starts at `x`.
+    ///
+    /// Transparent pixels use a `None` color.
+    run: (Option<C>, u32),
+
+    /// Color of the pixel after the run, if it was already read.
+    next: Option<Option<C>>,
+}
+
+impl<'a, C> RowRuns<'a, C>
+where
+    C: PixelColor,
+{
+    fn new(pixel: &'a mut dyn FnMut(Point) -> Option<C>, y: i32, width: i32) -> Self {
+        Self {
+            pixel,
+            y,
+            width,
+            x: 0,
+            run: (None, 0),
+            next: None,
+        }
+    }
+
+    /// Reads the run which starts at `x` until it is at least `limit` pixels long or its end is
+    /// reached.
+    ///
+    /// Returns the color and the known length of the run. A length of `0` is returned at the end
+    /// of the row.
+    fn read_run(&mut self, limit: u32) -> (Option<C>, u32) {
+        if self.run.1 == 0 {
+            if self.x >= self.width {
+                return (None, 0);
+            }
+
+            let color = match self.next.take() {
+                Some(color) => color,
+                None => (self.pixel)(Point::new(self.x, self.y)),
+            };
+            self.run = (color, 1);
+        }
+
+        while self.run.1 < limit && self.next.is_none() {
+            let x = self.x + self.run.1 as i32;
+            if x >= self.width {
+                break;
+            }
+
+            let color = (self.pixel)(Point::new(x, self.y));
+            if color == self.run.0 {
+                self.run.1 += 1;
+            } else {
+                self.next = Some(color);
+            }
+        }
+
+        self.run
+    }
+
+    /// Marks the given number of pixels at the start of the run as drawn.
+    fn consume(&mut self, length: u32) {
+        self.x += length as i32;
+        self.run.1 -= length;
+    }
+}
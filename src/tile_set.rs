@@ -0,0 +1,260 @@
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+};
+
+/// Tile set.
+///
+/// A tile set splits a single image, like a sprite sheet or a glyph atlas, into equally sized
+/// tiles. The tiles are arranged in a grid, which can be offset from the edges of the image by a
+/// margin and can contain spacing between the individual tiles.
+///
+/// Tiles are numbered from left to right and top to bottom, starting at `0` for the top left tile.
+/// Each tile is returned as a [`Tile`], which can be drawn like any other image. Only the pixels
+/// inside the tile are read from the image data when a tile is drawn.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+/// use tinybmp::{Bmp, TileSet};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+///
+/// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/chessboard-8px-color-16bit.bmp"))
+///     .unwrap();
+///
+/// // Split the 8x8px image into 4x4px tiles.
+/// let tile_set = TileSet::new(bmp, Size::new(4, 4));
+/// assert_eq!(tile_set.len(), 4);
+///
+/// // Draw the bottom right tile.
+/// let tile = tile_set.tile_at(1, 1).unwrap();
+/// Image::new(&tile, Point::new(10, 20)).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`Tile`]: struct.Tile.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TileSet<T> {
+    image: T,
+    tile_size: Size,
+    spacing: u32,
+    margin: u32,
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> defmt::Format for TileSet<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "TileSet {{ image: {}, tile_size: Size {{ width: {=u32}, height: {=u32} }}, spacing: {=u32}, margin: {=u32} }}",
+            self.image,
+            self.tile_size.width,
+            self.tile_size.height,
+            self.spacing,
+            self.margin,
+        )
+    }
+}
+
+impl<T> TileSet<T>
+where
+    T: GetPixel + Clone,
+{
+    /// Creates a new tile set.
+    ///
+    /// By default the tile set doesn't use any spacing or margin.
+    pub fn new(image: T, tile_size: Size) -> Self {
+        Self {
+            image,
+            tile_size,
+            spacing: 0,
+            margin: 0,
+        }
+    }
+
+    /// Sets the spacing between adjacent tiles in pixels.
+    pub fn with_spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Sets the margin between the edges of the image and the tiles in pixels.
+    pub fn with_margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the size of a single tile.
+    pub fn tile_size(&self) -> Size {
+        self.tile_size
+    }
+
+    /// Returns the number of tile columns.
+    pub fn columns(&self) -> u32 {
+        self.tile_count(self.image.size().width, self.tile_size.width)
+    }
+
+    /// Returns the number of tile rows.
+    pub fn rows(&self) -> u32 {
+        self.tile_count(self.image.size().height, self.tile_size.height)
+    }
+
+    /// Returns the total number of tiles.
+    ///
+    /// The number of tiles is limited to `u32::MAX`.
+    pub fn len(&self) -> u32 {
+        self.columns().saturating_mul(self.rows())
+    }
+
+    /// Returns `true` if the tile set doesn't contain any tiles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the tile with the given index.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn tile(&self, index: u32) -> Option<Tile<T>> {
+        let columns = self.columns();
+        if columns == 0 {
+            return None;
+        }
+
+        self.tile_at(index % columns, index / columns)
+    }
+
+    /// Returns the tile in the given column and row.
+    ///
+    /// `None` is returned if `column` or `row` are out of bounds.
+    pub fn tile_at(&self, column: u32, row: u32) -> Option<Tile<T>> {
+        if column >= self.columns() || row >= self.rows() {
+            return None;
+        }
+
+        let top_left = Point::new(
+            self.tile_offset(column, self.tile_size.width)?,
+            self.tile_offset(row, self.tile_size.height)?,
+        );
+
+        Some(Tile {
+            image: self.image.clone(),
+            area: Rectangle::new(top_left, self.tile_size),
+        })
+    }
+
+    /// Returns a reference to the underlying image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+
+    /// Returns the number of tiles along one axis.
+    fn tile_count(&self, image_length: u32, tile_length: u32) -> u32 {
+        if tile_length == 0 {
+            return 0;
+        }
+
+        let available = image_length.saturating_sub(self.margin.saturating_mul(2));
+        if available < tile_length {
+            return 0;
+        }
+
+        // The saturated stride is still larger than the remaining length, if the addition
+        // overflows.
+        (available - tile_length) / tile_length.saturating_add(self.spacing) + 1
+    }
+
+    /// Returns the offset of the tile with the given index along one axis.
+    fn tile_offset(&self, index: u32, tile_length: u32) -> Option<i32> {
+        // The stride isn't used for the first tile, which allows arbitrarily large spacings.
+        let offset = if index == 0 {
+            self.margin
+        } else {
+            let stride = tile_length.checked_add(self.spacing)?;
+            index.checked_mul(stride)?.checked_add(self.margin)?
+        };
+
+        i32::try_from(offset).ok()
+    }
+}
+
+/// A single tile in a tile set.
+///
+/// See the [`TileSet`] documentation for more information.
+///
+/// [`TileSet`]: struct.TileSet.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Tile<T> {
+    image: T,
+    area: Rectangle,
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> defmt::Format for Tile<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Tile {{ image: {}, area: Rectangle {{ top_left: Point {{ x: {=i32}, y: {=i32} }}, size: Size {{ width: {=u32}, height: {=u32} }} }} }}",
+            self.image,
+            self.area.top_left.x,
+            self.area.top_left.y,
+            self.area.size.width,
+            self.area.size.height,
+        )
+    }
+}
+
+impl<T> Tile<T> {
+    /// Returns the area of the tile in the underlying image.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<T> OriginDimensions for Tile<T> {
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}
+
+impl<T> GetPixel for Tile<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        let size = self.area.size;
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return None;
+        }
+
+        self.image.pixel(self.area.top_left + p)
+    }
+}
+
+impl<T> ImageDrawable for Tile<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        draw_opaque_spans(target, self.area.size, |p| {
+            self.image.pixel(self.area.top_left + p)
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
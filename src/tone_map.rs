@@ -0,0 +1,48 @@
+//! Tone mapping of 64 BPP images.
+//!
+//! 64 BPP images store each channel as a signed 16 bit value in the s2.13 fixed point format,
+//! with `8192` representing `1.0`. The values use a linear gamma and can be outside the `0.0`
+//! to `1.0` range.
+
+/// Linear value which corresponds to `1.0`.
+const ONE: i16 = 1 << 13;
+
+/// Smallest linear values which are mapped to the sRGB values `1` to `255`.
+///
+/// Using a lookup table avoids floating point math, which isn't available in `core`.
+const SRGB_THRESHOLDS: [u16; 255] = [
+    2, 4, 7, 9, 12, 14, 17, 19, 22, 24, 27, 29, 32, 35, 38, 41, 45, 48, 52, 56, 60, 64, 68, 73, 78,
+    83, 88, 93, 98, 104, 110, 116, 122, 128, 135, 142, 149, 156, 163, 170, 178, 186, 194, 203, 211,
+    220, 229, 238, 247, 257, 267, 277, 287, 297, 308, 319, 330, 341, 353, 365, 377, 389, 401, 414,
+    427, 440, 454, 467, 481, 495, 509, 524, 539, 554, 569, 585, 600, 617, 633, 649, 666, 683, 700,
+    718, 736, 754, 772, 791, 809, 828, 848, 867, 887, 907, 928, 948, 969, 990, 1012, 1034, 1055,
+    1078, 1100, 1123, 1146, 1169, 1193, 1217, 1241, 1266, 1290, 1315, 1341, 1366, 1392, 1418, 1444,
+    1471, 1498, 1525, 1553, 1581, 1609, 1637, 1666, 1695, 1724, 1754, 1784, 1814, 1844, 1875, 1906,
+    1938, 1969, 2001, 2034, 2066, 2099, 2132, 2166, 2199, 2233, 2268, 2303, 2338, 2373, 2409, 2444,
+    2481, 2517, 2554, 2591, 2629, 2667, 2705, 2743, 2782, 2821, 2860, 2900, 2940, 2981, 3021, 3062,
+    3104, 3145, 3187, 3229, 3272, 3315, 3358, 3402, 3446, 3490, 3535, 3580, 3625, 3670, 3716, 3763,
+    3809, 3856, 3903, 3951, 3999, 4047, 4096, 4145, 4194, 4244, 4294, 4344, 4394, 4445, 4497, 4548,
+    4600, 4653, 4706, 4759, 4812, 4866, 4920, 4974, 5029, 5084, 5140, 5196, 5252, 5308, 5365, 5423,
+    5480, 5538, 5597, 5655, 5714, 5774, 5833, 5894, 5954, 6015, 6076, 6138, 6200, 6262, 6325, 6388,
+    6451, 6515, 6579, 6643, 6708, 6773, 6839, 6905, 6971, 7038, 7105, 7173, 7240, 7309, 7377, 7446,
+    7515, 7585, 7655, 7725, 7796, 7867, 7939, 8011, 8083, 8156,
+];
+
+/// Converts a 64 BPP pixel into a raw `Rgb888` value.
+///
+/// The channels are clamped to the `0.0` to `1.0` range and encoded using the sRGB transfer
+/// function. The alpha channel is ignored.
+pub(crate) fn tone_map(pixel: &[u8]) -> u32 {
+    let channel = |index: usize| {
+        let value = i16::from_le_bytes([pixel[index * 2], pixel[index * 2 + 1]]);
+        let value = value.clamp(0, ONE) as u16;
+
+        SRGB_THRESHOLDS.partition_point(|threshold| *threshold <= value) as u32
+    };
+
+    let blue = channel(0);
+    let green = channel(1);
+    let red = channel(2);
+
+    red << 16 | green << 8 | blue
+}
@@ -0,0 +1,198 @@
+use super::embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use super::{
+    draw::{draw_opaque_spans, SubImageTarget},
+    get_pixel::GetPixel,
+};
+
+/// Clockwise rotation.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rotation {
+    /// No rotation.
+    #[default]
+    Deg0,
+    /// Rotation by 90 degrees clockwise.
+    Deg90,
+    /// Rotation by 180 degrees.
+    Deg180,
+    /// Rotation by 270 degrees clockwise.
+    Deg270,
+}
+
+impl Rotation {
+    fn quarter_turns(self) -> u8 {
+        match self {
+            Self::Deg0 => 0,
+            Self::Deg90 => 1,
+            Self::Deg180 => 2,
+            Self::Deg270 => 3,
+        }
+    }
+
+    fn from_quarter_turns(turns: u8) -> Self {
+        match turns % 4 {
+            0 => Self::Deg0,
+            1 => Self::Deg90,
+            2 => Self::Deg180,
+            _ => Self::Deg270,
+        }
+    }
+
+    /// Returns the combined rotation of `self` followed by `other`.
+    fn then(self, other: Self) -> Self {
+        Self::from_quarter_turns(self.quarter_turns() + other.quarter_turns())
+    }
+
+    /// Returns the rotation which reverses `self`.
+    fn inverse(self) -> Self {
+        Self::from_quarter_turns(4 - self.quarter_turns())
+    }
+
+    /// Returns `true` if the rotation swaps the width and height of an image.
+    fn swaps_dimensions(self) -> bool {
+        matches!(self, Self::Deg90 | Self::Deg270)
+    }
+}
+
+/// Rotated and/or flipped view of an image.
+///
+/// A `Transformed` image is created by calling one of the transformation methods, like
+/// [`Bmp::rotated`] or [`DynamicBmp::flipped_horizontal`]. Transformations can be chained, for
+/// example `bmp.rotated(Rotation::Deg90).flipped_vertical()`.
+///
+/// The pixels are read in the transformed order directly from the BMP image data without the use
+/// of an intermediate buffer.
+///
+/// [`Bmp::rotated`]: struct.Bmp.html#method.rotated
+/// [`DynamicBmp::flipped_horizontal`]: struct.DynamicBmp.html#method.flipped_horizontal
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transformed<T> {
+    image: T,
+
+    /// Rotation which is applied after the image was flipped.
+    rotation: Rotation,
+
+    /// Horizontal flip which is applied before the image is rotated.
+    flipped: bool,
+}
+
+impl<T> Transformed<T> {
+    /// Creates an untransformed view of an image.
+    ///
+    /// This can be used to transform images which don't provide their own transformation
+    /// methods, for example a [`Scaled`] image.
+    ///
+    /// [`Scaled`]: struct.Scaled.html
+    pub fn new(image: T) -> Self {
+        Self {
+            image,
+            rotation: Rotation::Deg0,
+            flipped: false,
+        }
+    }
+
+    /// Rotates the image clockwise.
+    pub fn rotated(mut self, rotation: Rotation) -> Self {
+        self.rotation = self.rotation.then(rotation);
+        self
+    }
+
+    /// Flips the image horizontally.
+    pub fn flipped_horizontal(mut self) -> Self {
+        // Flipping a rotated image is equivalent to flipping the image first and rotating it in
+        // the opposite direction.
+        self.rotation = self.rotation.inverse();
+        self.flipped = !self.flipped;
+        self
+    }
+
+    /// Flips the image vertically.
+    pub fn flipped_vertical(self) -> Self {
+        self.flipped_horizontal().rotated(Rotation::Deg180)
+    }
+
+    /// Returns a reference to the untransformed image.
+    pub fn as_inner(&self) -> &T {
+        &self.image
+    }
+}
+
+impl<T> Transformed<T>
+where
+    T: OriginDimensions,
+{
+    /// Maps a point in the transformed image to the corresponding point in the source image.
+    fn source_point(&self, p: Point) -> Point {
+        let Size { width, height } = self.image.size();
+        let (w, h) = (width as i32, height as i32);
+
+        let p = match self.rotation {
+            Rotation::Deg0 => p,
+            Rotation::Deg90 => Point::new(p.y, h - 1 - p.x),
+            Rotation::Deg180 => Point::new(w - 1 - p.x, h - 1 - p.y),
+            Rotation::Deg270 => Point::new(w - 1 - p.y, p.x),
+        };
+
+        if self.flipped {
+            Point::new(w - 1 - p.x, p.y)
+        } else {
+            p
+        }
+    }
+}
+
+impl<T> OriginDimensions for Transformed<T>
+where
+    T: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        let size = self.image.size();
+
+        if self.rotation.swaps_dimensions() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+}
+
+impl<T> GetPixel for Transformed<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        let size = self.size();
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return None;
+        }
+
+        self.image.pixel(self.source_point(p))
+    }
+}
+
+impl<T> ImageDrawable for Transformed<T>
+where
+    T: GetPixel,
+{
+    type Color = T::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        draw_opaque_spans(target, self.size(), |p| {
+            self.image.pixel(self.source_point(p))
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut SubImageTarget::new(target, area))
+    }
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{image::Image, mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+use tinybmp::{Bmp, BmpAnimation, FrameDurations, StripDirection};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+fn bmp() -> Bmp<'static, Rgb565> {
+    Bmp::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap()
+}
+
+#[test]
+fn horizontal_strip() {
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        4,
+        FrameDurations::Uniform(100),
+    );
+    assert_eq!(animation.frame_count(), 4);
+    assert_eq!(animation.total_duration(), 400);
+
+    let frame = animation.frame(1).unwrap();
+    assert_eq!(frame.size(), Size::new(1, 2));
+    draw_image(frame).assert_pattern(&[
+        "R", //
+        "M", //
+    ]);
+
+    draw_image(animation.frame(3).unwrap()).assert_pattern(&[
+        "Y", //
+        "W", //
+    ]);
+
+    assert_eq!(animation.frame(4), None);
+}
+
+#[test]
+fn vertical_strip() {
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Vertical,
+        2,
+        FrameDurations::Uniform(10),
+    );
+    assert_eq!(animation.frame_count(), 2);
+
+    draw_image(animation.frame(0).unwrap()).assert_pattern(&["KRGY"]);
+    draw_image(animation.frame(1).unwrap()).assert_pattern(&["BMCW"]);
+}
+
+#[test]
+fn frame_at_uniform() {
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        4,
+        FrameDurations::Uniform(100),
+    );
+
+    assert_eq!(animation.frame_at(0), animation.frame(0));
+    assert_eq!(animation.frame_at(99), animation.frame(0));
+    assert_eq!(animation.frame_at(100), animation.frame(1));
+    assert_eq!(animation.frame_at(399), animation.frame(3));
+    assert_eq!(animation.frame_at(400), animation.frame(0));
+    assert_eq!(animation.frame_at(1250), animation.frame(0));
+}
+
+#[test]
+fn frame_at_per_frame() {
+    let durations = [100, 50, 0, 200];
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        4,
+        FrameDurations::PerFrame(&durations),
+    );
+    assert_eq!(animation.total_duration(), 350);
+    assert_eq!(animation.frame_duration(1), Some(50));
+    assert_eq!(animation.frame_duration(4), None);
+
+    assert_eq!(animation.frame_at(120), animation.frame(1));
+    assert_eq!(animation.frame_at(150), animation.frame(3));
+    assert_eq!(animation.frame_at(350), animation.frame(0));
+}
+
+#[test]
+fn frame_at_table() {
+    let table = [
+        10, 0, 0, 0, //
+        20, 0, 0, 0, //
+    ];
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        4,
+        FrameDurations::Table(&table),
+    );
+    assert_eq!(animation.frame_duration(0), Some(10));
+    assert_eq!(animation.frame_duration(1), Some(20));
+    assert_eq!(animation.frame_duration(2), Some(0));
+    assert_eq!(animation.total_duration(), 30);
+
+    assert_eq!(animation.frame_at(5), animation.frame(0));
+    assert_eq!(animation.frame_at(15), animation.frame(1));
+    assert_eq!(animation.frame_at(31), animation.frame(0));
+}
+
+#[test]
+fn zero_duration() {
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        4,
+        FrameDurations::Uniform(0),
+    );
+
+    assert_eq!(animation.frame_at(1000), animation.frame(0));
+}
+
+#[test]
+fn no_frames() {
+    let animation = BmpAnimation::new(
+        bmp(),
+        StripDirection::Horizontal,
+        0,
+        FrameDurations::Uniform(10),
+    );
+
+    assert_eq!(animation.frame_count(), 0);
+    assert_eq!(animation.frame(0), None);
+    assert_eq!(animation.frame_at(0), None);
+}
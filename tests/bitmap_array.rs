@@ -0,0 +1,128 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use tinybmp::{BitmapArray, Bmp, Bpp, FileType, HeaderType, ParseError, RawBmp};
+
+const BITMAP_ARRAY: &[u8] = include_bytes!("./bitmap-array.bmp");
+
+#[test]
+fn bitmaps() {
+    let array = BitmapArray::from_slice(BITMAP_ARRAY).unwrap();
+    let mut bitmaps = array.bitmaps();
+
+    let colors = RawBmp::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let bmp = bitmaps.next().unwrap().unwrap();
+    assert_eq!(bmp.size(), Size::new(4, 2));
+    assert_eq!(bmp.color_bpp(), Bpp::Bits24);
+    assert_eq!(bmp.header().file_type, FileType::Bitmap);
+    assert!(bmp.pixels().eq(colors.pixels()));
+
+    let chessboard = RawBmp::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")).unwrap();
+    let bmp = bitmaps.next().unwrap().unwrap();
+    assert_eq!(bmp.size(), Size::new(8, 8));
+    assert_eq!(bmp.color_bpp(), Bpp::Bits1);
+    assert!(bmp.pixels().eq(chessboard.pixels()));
+
+    assert_eq!(bitmaps.next(), None);
+}
+
+#[test]
+fn core_headers() {
+    const CORE_ARRAY: &[u8] = include_bytes!("./bitmap-array-core.bmp");
+
+    let array = BitmapArray::from_slice(CORE_ARRAY).unwrap();
+    let bitmaps = array.bitmaps().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(bitmaps.len(), 3);
+
+    for bmp in &bitmaps {
+        assert_eq!(bmp.header().header_type, HeaderType::Core);
+    }
+
+    // The first bitmap uses an 8 BPP color table with 3 byte entries.
+    assert_eq!(bitmaps[0].color_bpp(), Bpp::Bits8);
+    let bmp = Bmp::<Rgb888>::from_slice(CORE_ARRAY).unwrap();
+    let colors = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    assert!(bmp.pixels().eq(colors.pixels()));
+
+    let chessboard = RawBmp::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")).unwrap();
+    assert_eq!(bitmaps[1].size(), Size::new(8, 8));
+    assert_eq!(bitmaps[1].color_bpp(), Bpp::Bits1);
+    assert!(bitmaps[1].pixels().eq(chessboard.pixels()));
+
+    assert_eq!(bitmaps[2].size(), Size::new(4, 2));
+    assert_eq!(bitmaps[2].color_bpp(), Bpp::Bits24);
+    assert!(bitmaps[2].pixels().eq(colors.as_raw().pixels()));
+}
+
+#[test]
+fn from_slice_returns_first_bitmap() {
+    let array = BitmapArray::from_slice(BITMAP_ARRAY).unwrap();
+    let first = array.bitmaps().next().unwrap().unwrap();
+
+    assert_eq!(RawBmp::from_slice(BITMAP_ARRAY), Ok(first));
+}
+
+#[test]
+fn invalid_signature() {
+    let file = include_bytes!("./colors_rgb888_24bit.bmp");
+
+    assert_eq!(
+        BitmapArray::from_slice(file),
+        Err(ParseError::InvalidFileSignature)
+    );
+}
+
+#[test]
+fn invalid_next_offset() {
+    let mut file = BITMAP_ARRAY.to_vec();
+    // Link the first entry to an offset inside its own header.
+    file[6..10].copy_from_slice(&1u32.to_le_bytes());
+
+    let array = BitmapArray::from_slice(&file).unwrap();
+    assert_eq!(array.bitmaps().count(), 1);
+}
+
+#[test]
+fn truncated_entry() {
+    let mut file = BITMAP_ARRAY.to_vec();
+    // Link the first entry to the end of the file.
+    let len = file.len() as u32;
+    file[6..10].copy_from_slice(&len.to_le_bytes());
+
+    let array = BitmapArray::from_slice(&file).unwrap();
+    let mut bitmaps = array.bitmaps();
+    assert!(bitmaps.next().unwrap().is_ok());
+    assert_eq!(bitmaps.next(), Some(Err(ParseError::UnexpectedEndOfFile)));
+    assert_eq!(bitmaps.next(), None);
+}
+
+#[test]
+fn os2_file_types() {
+    let expected = [
+        (b"CI", FileType::ColorIcon),
+        (b"CP", FileType::ColorPointer),
+        (b"IC", FileType::Icon),
+        (b"PT", FileType::Pointer),
+    ];
+
+    for (signature, file_type) in expected {
+        let mut file = include_bytes!("./chessboard-8px-1bit.bmp").to_vec();
+        file[0..2].copy_from_slice(signature);
+
+        let bmp = RawBmp::from_slice(&file).unwrap();
+        assert_eq!(bmp.header().file_type, file_type);
+    }
+}
+
+#[test]
+fn nested_bitmap_array() {
+    let mut file = BITMAP_ARRAY.to_vec();
+    file[14..16].copy_from_slice(b"BA");
+
+    assert_eq!(
+        RawBmp::from_slice(&file),
+        Err(ParseError::InvalidFileSignature)
+    );
+}
@@ -1,28 +1,44 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
 use embedded_graphics::{
     pixelcolor::{BinaryColor, Rgb888},
     prelude::*,
 };
-use tinybmp::{Bmp, Bpp, Header, RawBmp, RowOrder};
+use tinybmp::{Bmp, Bpp, CompressionMethod, FileType, HeaderType, RawBmp, Resolution, RowOrder};
 
 #[test]
 fn chessboard_8px_1bit() {
     let bmp =
         RawBmp::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")).expect("Failed to parse");
 
+    let header = bmp.header();
+    assert_eq!(header.file_type, FileType::Bitmap);
+    assert_eq!(header.file_size, 94);
+    assert_eq!(header.image_data_start, 62);
+    assert_eq!(header.bpp, Bpp::Bits1);
+    assert_eq!(header.image_size, Size::new(8, 8));
+    assert_eq!(header.image_data_len, 32);
+    assert_eq!(header.channel_masks, None);
+    assert_eq!(header.row_order, RowOrder::BottomUp);
+    assert_eq!(header.header_type, HeaderType::Info);
+    assert_eq!(header.compression_method, CompressionMethod::Rgb);
     assert_eq!(
-        bmp.header(),
-        &Header {
-            file_size: 94,
-            image_data_start: 62,
-            bpp: Bpp::Bits1,
-            image_size: Size::new(8, 8),
-            image_data_len: 32,
-            channel_masks: None,
-            row_order: RowOrder::BottomUp,
+        header.resolution,
+        Resolution {
+            pixels_per_meter_x: 3780,
+            pixels_per_meter_y: 3780,
         }
     );
+    assert_eq!(header.color_planes, 1);
+    assert_eq!(header.colors_used, 2);
+    assert_eq!(header.colors_important, 2);
+    assert_eq!(header.rendering_intent, None);
+    assert_eq!(header.color_space, None);
 
     assert_eq!(bmp.image_data().len(), 94 - 62);
+    assert_eq!(bmp.header().resolution.dpi(), (96, 96));
 }
 
 #[test]
@@ -88,7 +104,7 @@ fn chessboard_8px_1bit_iter() {
 
     let pixels: Vec<u32> = bmp
         .pixels()
-        .map(|Pixel(_pos, color)| color.into_storage().into())
+        .map(|Pixel(_pos, color)| color.into_storage())
         .collect();
 
     // 8px x 8px image. Check that iterator returns all pixels in it
@@ -122,7 +138,7 @@ fn chessboard_8px_1bit_iter_inverted() {
 
     let pixels: Vec<u32> = bmp
         .pixels()
-        .map(|Pixel(_pos, color)| color.into_storage().into())
+        .map(|Pixel(_pos, color)| color.into_storage())
         .collect();
 
     // 8px x 8px image. Check that iterator returns all pixels in it
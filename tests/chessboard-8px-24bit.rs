@@ -1,5 +1,12 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
 use embedded_graphics::prelude::*;
-use tinybmp::{Bpp, Header, RawBmp, RowOrder};
+use tinybmp::{
+    Bpp, CalibratedRgb, CieXyz, ColorSpace, CompressionMethod, FileType, HeaderType, RawBmp,
+    Resolution, RowOrder,
+};
 
 const DATA: &[u8] = include_bytes!("./chessboard-8px-24bit.bmp");
 
@@ -7,20 +14,38 @@ const DATA: &[u8] = include_bytes!("./chessboard-8px-24bit.bmp");
 fn chessboard_8px_24bit() {
     let bmp = RawBmp::from_slice(DATA).expect("Failed to parse");
 
+    let header = bmp.header();
+    assert_eq!(header.file_type, FileType::Bitmap);
+    assert_eq!(header.file_size, 314);
+    assert_eq!(header.image_data_start, 122);
+    assert_eq!(header.bpp, Bpp::Bits24);
+    assert_eq!(header.image_size, Size::new(8, 8));
+    assert_eq!(header.image_data_len, 192);
+    assert_eq!(header.channel_masks, None);
+    assert_eq!(header.row_order, RowOrder::BottomUp);
+    assert_eq!(header.header_type, HeaderType::V4);
+    assert_eq!(header.compression_method, CompressionMethod::Rgb);
     assert_eq!(
-        bmp.header(),
-        &Header {
-            file_size: 314,
-            image_data_start: 122,
-            bpp: Bpp::Bits24,
-            image_size: Size::new(8, 8),
-            image_data_len: 192,
-            channel_masks: None,
-            row_order: RowOrder::BottomUp,
+        header.resolution,
+        Resolution {
+            pixels_per_meter_x: 2835,
+            pixels_per_meter_y: 2835,
         }
     );
+    assert_eq!(header.color_planes, 1);
+    assert_eq!(header.colors_used, 0);
+    assert_eq!(header.colors_important, 0);
+    assert_eq!(header.rendering_intent, None);
+    assert_eq!(
+        header.color_space,
+        Some(ColorSpace::CalibratedRgb(CalibratedRgb {
+            blue: CieXyz { x: 0, y: 0, z: 2 },
+            ..CalibratedRgb::default()
+        }))
+    );
 
     assert_eq!(bmp.image_data().len(), 314 - 122);
+    assert_eq!(bmp.header().resolution.dpi(), (72, 72));
 }
 
 #[test]
@@ -30,18 +55,35 @@ fn chessboard_8px_24bit_truncated_iter() {
 
     let bmp = RawBmp::from_slice(truncated_data).expect("Failed to parse");
 
+    let header = bmp.header();
+    assert_eq!(header.file_type, FileType::Bitmap);
+    assert_eq!(header.file_size, 314);
+    assert_eq!(header.image_data_start, 122);
+    assert_eq!(header.bpp, Bpp::Bits24);
+    assert_eq!(header.image_size, Size::new(8, 8));
+    assert_eq!(header.image_data_len, 192);
+    assert_eq!(header.channel_masks, None);
+    assert_eq!(header.row_order, RowOrder::BottomUp);
+    assert_eq!(header.header_type, HeaderType::V4);
+    assert_eq!(header.compression_method, CompressionMethod::Rgb);
     assert_eq!(
-        bmp.header(),
-        &Header {
-            file_size: 314,
-            image_data_start: 122,
-            bpp: Bpp::Bits24,
-            image_size: Size::new(8, 8),
-            image_data_len: 192,
-            channel_masks: None,
-            row_order: RowOrder::BottomUp,
+        header.resolution,
+        Resolution {
+            pixels_per_meter_x: 2835,
+            pixels_per_meter_y: 2835,
         }
     );
+    assert_eq!(header.color_planes, 1);
+    assert_eq!(header.colors_used, 0);
+    assert_eq!(header.colors_important, 0);
+    assert_eq!(header.rendering_intent, None);
+    assert_eq!(
+        header.color_space,
+        Some(ColorSpace::CalibratedRgb(CalibratedRgb {
+            blue: CieXyz { x: 0, y: 0, z: 2 },
+            ..CalibratedRgb::default()
+        }))
+    );
 
     let pixels: Vec<u32> = bmp.pixels().map(|pixel| pixel.color).collect();
 
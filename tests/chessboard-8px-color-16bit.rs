@@ -1,23 +1,44 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
 use embedded_graphics::prelude::*;
-use tinybmp::{Bpp, ChannelMasks, Header, RawBmp, RowOrder};
+use tinybmp::{
+    Bpp, ChannelMasks, ColorSpace, CompressionMethod, FileType, HeaderType, RawBmp,
+    RenderingIntent, Resolution, RowOrder,
+};
 
 #[test]
 fn chessboard_8px_color_16bit() {
     let bmp = RawBmp::from_slice(include_bytes!("./chessboard-8px-color-16bit.bmp"))
         .expect("Failed to parse");
 
+    let header = bmp.header();
+    assert_eq!(header.file_type, FileType::Bitmap);
+    assert_eq!(header.file_size, 266);
+    assert_eq!(header.image_data_start, 138);
+    assert_eq!(header.bpp, Bpp::Bits16);
+    assert_eq!(header.image_size, Size::new(8, 8));
+    assert_eq!(header.image_data_len, 128);
+    assert_eq!(header.channel_masks, Some(ChannelMasks::RGB565));
+    assert_eq!(header.row_order, RowOrder::BottomUp);
+    assert_eq!(header.header_type, HeaderType::V5);
+    assert_eq!(header.compression_method, CompressionMethod::Bitfields);
     assert_eq!(
-        bmp.header(),
-        &Header {
-            file_size: 266,
-            image_data_start: 138,
-            bpp: Bpp::Bits16,
-            image_size: Size::new(8, 8),
-            image_data_len: 128,
-            channel_masks: Some(ChannelMasks::RGB565),
-            row_order: RowOrder::BottomUp,
+        header.resolution,
+        Resolution {
+            pixels_per_meter_x: 2835,
+            pixels_per_meter_y: 2835,
         }
     );
+    assert_eq!(header.color_planes, 1);
+    assert_eq!(header.colors_used, 0);
+    assert_eq!(header.colors_important, 0);
+    assert_eq!(
+        header.rendering_intent,
+        Some(RenderingIntent::RelativeColorimetric)
+    );
+    assert_eq!(header.color_space, Some(ColorSpace::Srgb));
 
     assert_eq!(bmp.image_data().len(), 266 - 138);
 }
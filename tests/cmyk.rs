@@ -0,0 +1,82 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{image::Image, mock_display::MockDisplay, pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, CompressionMethod, DynamicBmp, RawBmp};
+
+const EXPECTED: &[&str] = &[
+    "WCKR", //
+];
+
+fn assert_colors(file: &[u8], compression_method: CompressionMethod) {
+    let raw = RawBmp::from_slice(file).unwrap();
+    assert_eq!(raw.header().compression_method, compression_method);
+
+    let bmp = Bmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+}
+
+#[test]
+fn cmyk_32bpp() {
+    let file = include_bytes!("./cmyk-32bpp.bmp");
+    assert_colors(file, CompressionMethod::Cmyk);
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+
+    let bmp = RawBmp::from_slice(file).unwrap();
+    assert_eq!(bmp.pixel(Point::new(1, 0)), Some(0x00FFFF));
+}
+
+#[test]
+fn cmyk_8bpp() {
+    let file = include_bytes!("./cmyk-8bpp.bmp");
+    assert_colors(file, CompressionMethod::Cmyk);
+
+    // The raw values of indexed images are the color table indices.
+    let bmp = RawBmp::from_slice(file).unwrap();
+    assert_eq!(bmp.pixel(Point::new(1, 0)), Some(1));
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+}
+
+#[test]
+fn partial_black() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./cmyk-32bpp.bmp")).unwrap();
+    let mut file = include_bytes!("./cmyk-32bpp.bmp").to_vec();
+    let image_data_start = bmp.as_raw().header().image_data_start;
+
+    // Set the black channel of the first pixel to 50%.
+    file[image_data_start] = 128;
+
+    let bmp = Bmp::<Rgb888>::from_slice(&file).unwrap();
+    assert_eq!(bmp.pixel(Point::zero()), Some(Rgb888::new(127, 127, 127)));
+}
+
+#[test]
+fn cmyk_rle8() {
+    let file = include_bytes!("./cmyk-rle8.bmp");
+    assert_colors(file, CompressionMethod::CmykRle8);
+
+    let bmp = RawBmp::from_slice(file).unwrap();
+    assert_eq!(bmp.pixel(Point::new(2, 0)), Some(2));
+}
+
+#[test]
+fn cmyk_rle4() {
+    let file = include_bytes!("./cmyk-rle4.bmp");
+    assert_colors(file, CompressionMethod::CmykRle4);
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+}
@@ -1,5 +1,9 @@
-use embedded_graphics::{pixelcolor::Rgb888, prelude::IntoStorage, Pixel};
-use tinybmp::Bmp;
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, ColorType, DynamicBmp};
 
 #[test]
 fn colors_8bpp_indexed() {
@@ -24,3 +28,19 @@ fn colors_8bpp_indexed() {
 
     assert_eq!(pixels, expected_pixels);
 }
+
+#[test]
+fn colors_8bpp_indexed_dynamic() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(include_bytes!("./colors_8bpp_indexed.bmp"))
+        .expect("Failed to parse");
+
+    // The color table contains colors and must not be decoded as grayscale.
+    assert_eq!(bmp.color_type(), ColorType::Rgb888);
+
+    let expected = Bmp::<'_, Rgb888>::from_slice(include_bytes!("./colors_8bpp_non_indexed.bmp"))
+        .expect("Failed to parse non_indexed");
+
+    for p in expected.bounding_box().points() {
+        assert_eq!(bmp.pixel(p), expected.pixel(p), "{:?}", p);
+    }
+}
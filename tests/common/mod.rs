@@ -0,0 +1,13 @@
+//! Selects the embedded-graphics version which is used by the integration tests.
+//!
+//! The tests use the embedded-graphics 0.7 API of tinybmp if the `embedded-graphics-0_7` feature
+//! is enabled and the embedded-graphics 0.8 API in the `v0_8` module otherwise.
+
+// Not every test uses both crates.
+#![allow(unused_imports)]
+
+#[cfg(feature = "embedded-graphics-0_7")]
+pub use ::{embedded_graphics, tinybmp};
+
+#[cfg(not(feature = "embedded-graphics-0_7"))]
+pub use ::{embedded_graphics_0_8 as embedded_graphics, tinybmp::v0_8 as tinybmp};
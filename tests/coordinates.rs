@@ -1,3 +1,7 @@
+mod common;
+
+use common::tinybmp;
+
 use tinybmp::RawBmp;
 
 #[test]
@@ -0,0 +1,165 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, ChannelMasks, DynamicBmp, HeaderType, ParseError, RawBmp};
+
+/// Size of the BMP file header.
+const FILE_HEADER_SIZE: usize = 14;
+
+#[test]
+fn matches_bmp_file() {
+    let files: [&[u8]; 5] = [
+        include_bytes!("./chessboard-8px-1bit.bmp"),
+        include_bytes!("./chessboard-8px-24bit.bmp"),
+        include_bytes!("./chessboard-8px-color-16bit.bmp"),
+        include_bytes!("./colors_grey8.bmp"),
+        include_bytes!("./colors_rgb888_32bit.bmp"),
+    ];
+
+    for file in files {
+        let bmp = RawBmp::from_slice(file).unwrap();
+        let dib = RawBmp::from_dib_slice(&file[FILE_HEADER_SIZE..]).unwrap();
+
+        assert_eq!(
+            dib.header().image_data_start,
+            bmp.header().image_data_start - FILE_HEADER_SIZE
+        );
+        assert_eq!(
+            dib.header().file_size as usize,
+            file.len() - FILE_HEADER_SIZE
+        );
+        assert_eq!(dib.image_data(), bmp.image_data());
+        assert!(dib.pixels().eq(bmp.pixels()));
+    }
+}
+
+#[test]
+fn draw_dib() {
+    let file = include_bytes!("./colors_rgb888_24bit.bmp");
+
+    let bmp = Bmp::<Rgb888>::from_dib_slice(&file[FILE_HEADER_SIZE..]).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+
+    let bmp = DynamicBmp::<Rgb888>::from_dib_slice(&file[FILE_HEADER_SIZE..]).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn info_header_with_bitfields() {
+    let mut dib = Vec::new();
+
+    // BITMAPINFOHEADER
+    dib.extend_from_slice(&40u32.to_le_bytes());
+    dib.extend_from_slice(&2u32.to_le_bytes()); // width
+    dib.extend_from_slice(&1i32.to_le_bytes()); // height
+    dib.extend_from_slice(&1u16.to_le_bytes()); // color planes
+    dib.extend_from_slice(&16u16.to_le_bytes()); // bpp
+    dib.extend_from_slice(&3u32.to_le_bytes()); // BI_BITFIELDS
+    dib.extend_from_slice(&4u32.to_le_bytes()); // image data length
+    dib.extend_from_slice(&[0; 16]); // resolution and color counts
+
+    // Channel masks
+    dib.extend_from_slice(&0xF800u32.to_le_bytes());
+    dib.extend_from_slice(&0x07E0u32.to_le_bytes());
+    dib.extend_from_slice(&0x001Fu32.to_le_bytes());
+
+    // Image data
+    dib.extend_from_slice(&0xF800u16.to_le_bytes());
+    dib.extend_from_slice(&0x001Fu16.to_le_bytes());
+
+    let raw = RawBmp::from_dib_slice(&dib).unwrap();
+    assert_eq!(raw.header().image_data_start, 52);
+    assert_eq!(raw.header().channel_masks, Some(ChannelMasks::RGB565));
+
+    let bmp = Bmp::<Rgb565>::from_dib_slice(&dib).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(&["RB"]);
+}
+
+#[test]
+fn v2_header() {
+    let mut dib = Vec::new();
+
+    // BITMAPV2INFOHEADER
+    dib.extend_from_slice(&52u32.to_le_bytes());
+    dib.extend_from_slice(&2u32.to_le_bytes()); // width
+    dib.extend_from_slice(&1i32.to_le_bytes()); // height
+    dib.extend_from_slice(&1u16.to_le_bytes()); // color planes
+    dib.extend_from_slice(&16u16.to_le_bytes()); // bpp
+    dib.extend_from_slice(&3u32.to_le_bytes()); // BI_BITFIELDS
+    dib.extend_from_slice(&4u32.to_le_bytes()); // image data length
+    dib.extend_from_slice(&[0; 16]); // resolution and color counts
+    dib.extend_from_slice(&0xF800u32.to_le_bytes()); // red mask
+    dib.extend_from_slice(&0x07E0u32.to_le_bytes()); // green mask
+    dib.extend_from_slice(&0x001Fu32.to_le_bytes()); // blue mask
+
+    // Image data
+    dib.extend_from_slice(&0xF800u16.to_le_bytes());
+    dib.extend_from_slice(&0x001Fu16.to_le_bytes());
+
+    let raw = RawBmp::from_dib_slice(&dib).unwrap();
+    assert_eq!(raw.header().header_type, HeaderType::V2);
+    assert_eq!(raw.header().image_data_start, 52);
+    assert_eq!(raw.header().channel_masks, Some(ChannelMasks::RGB565));
+
+    let bmp = Bmp::<Rgb565>::from_dib_slice(&dib).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(&["RB"]);
+}
+
+#[test]
+fn truncated_os2_header() {
+    let file = include_bytes!("./chessboard-8px-1bit.bmp");
+    let bmp = RawBmp::from_slice(file).unwrap();
+
+    // The first 40 bytes of OS/2 2.x headers use the same layout as a `BITMAPINFOHEADER`.
+    let info_header = &file[FILE_HEADER_SIZE + 4..FILE_HEADER_SIZE + 40];
+    let color_table_and_data = &file[FILE_HEADER_SIZE + 40..];
+
+    // Lengths which are used by Windows headers, like 40 and 52 bytes, aren't OS/2 headers.
+    for length in [16, 20, 36, 48, 64] {
+        let mut dib = (length as u32).to_le_bytes().to_vec();
+        dib.extend(info_header.iter().copied().chain([0; 24]).take(length - 4));
+        dib.extend_from_slice(color_table_and_data);
+
+        let os2 = RawBmp::from_dib_slice(&dib).unwrap();
+        assert_eq!(os2.header().header_type, HeaderType::Os2V2, "{length}");
+        assert!(os2.pixels().eq(bmp.pixels()), "{length}");
+    }
+
+    for length in [13, 15, 65] {
+        let mut dib = (length as u32).to_le_bytes().to_vec();
+        dib.resize(length + 8, 0);
+
+        assert_eq!(
+            RawBmp::from_dib_slice(&dib),
+            Err(ParseError::UnsupportedHeaderLength(length as u32))
+        );
+    }
+}
+
+#[test]
+fn bmp_file_is_not_a_dib() {
+    let file = include_bytes!("./colors_rgb888_24bit.bmp");
+
+    assert!(RawBmp::from_dib_slice(file).is_err());
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{BinaryColor, Gray4, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, DynamicBmp, GetPixel};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+const GRAY_50: &[u8] = include_bytes!("./gray-50-8px-24bit.bmp");
+
+#[test]
+fn ordered_dither_binary() {
+    let bmp = Bmp::<Rgb888>::from_slice(GRAY_50).unwrap();
+
+    draw_image(bmp.ordered_dither::<BinaryColor>()).assert_pattern(&[
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+    ]);
+}
+
+#[test]
+fn ordered_dither_gray4() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(GRAY_50).unwrap();
+    let dithered = bmp.ordered_dither::<Gray4>();
+
+    let levels: Vec<u8> = Rectangle::new(Point::zero(), dithered.size())
+        .points()
+        .map(|p| dithered.pixel(p).unwrap().luma())
+        .collect();
+
+    assert!(levels.iter().all(|&level| level == 7 || level == 8));
+    assert!(levels.contains(&7));
+    assert!(levels.contains(&8));
+}
+
+#[test]
+fn ordered_dither_black_and_white() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let dithered = bmp.ordered_dither::<BinaryColor>();
+
+    assert_eq!(dithered.pixel(Point::new(0, 0)), Some(BinaryColor::Off));
+    assert_eq!(dithered.pixel(Point::new(3, 1)), Some(BinaryColor::On));
+}
+
+#[test]
+fn floyd_steinberg_binary() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(GRAY_50).unwrap();
+    let mut buffer = [0; 8];
+
+    draw_image(bmp.floyd_steinberg::<BinaryColor>(&mut buffer).unwrap()).assert_pattern(&[
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+        "#.#.#.#.", //
+        ".#.#.#.#", //
+    ]);
+}
+
+#[test]
+fn floyd_steinberg_is_repeatable() {
+    let bmp = Bmp::<Rgb888>::from_slice(GRAY_50).unwrap();
+    let mut buffer = [0; 8];
+    let dithered = bmp.floyd_steinberg::<Gray4>(&mut buffer).unwrap();
+
+    let mut first = MockDisplay::new();
+    Image::new(&dithered, Point::zero())
+        .draw(&mut first)
+        .unwrap();
+
+    let mut second = MockDisplay::new();
+    Image::new(&dithered, Point::zero())
+        .draw(&mut second)
+        .unwrap();
+
+    first.assert_eq(&second);
+}
+
+#[test]
+fn floyd_steinberg_buffer_too_small() {
+    let bmp = Bmp::<Rgb888>::from_slice(GRAY_50).unwrap();
+    let mut buffer = [0; 7];
+
+    assert!(bmp.floyd_steinberg::<BinaryColor>(&mut buffer).is_none());
+}
@@ -1,11 +1,15 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
 use embedded_graphics::{
     image::Image,
     mock_display::{ColorMapping, MockDisplay},
-    pixelcolor::{Gray8, Rgb555, Rgb565, Rgb888},
+    pixelcolor::{BinaryColor, Gray4, Gray8, Rgb555, Rgb565, Rgb888},
     prelude::*,
     primitives::Rectangle,
 };
-use tinybmp::{Bmp, DynamicBmp, RowOrder};
+use tinybmp::{Bmp, ColorType, DynamicBmp, RawBmp, RowOrder};
 
 #[test]
 fn negative_top_left() {
@@ -114,6 +118,16 @@ fn colors_rgb888_32bit_dynamic() {
     test_color_pattern_dynamic(include_bytes!("./colors_rgb888_32bit.bmp"));
 }
 
+#[test]
+fn colors_4bpp_indexed() {
+    test_color_pattern::<Rgb888>(include_bytes!("./colors_4bpp_indexed.bmp"));
+}
+
+#[test]
+fn colors_4bpp_indexed_dynamic() {
+    test_color_pattern_dynamic(include_bytes!("./colors_4bpp_indexed.bmp"));
+}
+
 #[test]
 fn colors_grey8() {
     let bmp: Bmp<Gray8> = Bmp::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
@@ -178,3 +192,115 @@ fn issue_8_height_is_negative() {
     bottom_up_display.assert_pattern(&["WK", "KK"]);
     top_down_display.assert_eq(&bottom_up_display);
 }
+
+#[test]
+fn colors_dynamic_binary_color() {
+    let bmp =
+        DynamicBmp::<BinaryColor>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    draw_image(bmp).assert_pattern(&[
+        "..##", //
+        "..##", //
+    ]);
+}
+
+#[test]
+fn colors_dynamic_threshold() {
+    let bmp = DynamicBmp::<Rgb565>::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap();
+
+    draw_image(bmp.threshold(100)).assert_pattern(&[
+        "..##", //
+        ".###", //
+    ]);
+
+    draw_image(bmp.threshold(200)).assert_pattern(&[
+        "...#", //
+        "...#", //
+    ]);
+}
+
+#[test]
+fn colors_threshold() {
+    let bmp = Bmp::<Gray8>::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
+
+    draw_image(bmp.threshold(0x88)).assert_pattern(&[".##"]);
+    draw_image(bmp.threshold(0x89)).assert_pattern(&["..#"]);
+}
+
+#[test]
+fn colors_grey8_dynamic_gray4() {
+    let bmp = DynamicBmp::<Gray4>::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
+    let display = draw_image(bmp);
+    display.assert_eq(&expected_image_gray().map(|c| c.into()));
+}
+
+#[test]
+fn chessboard_1bpp_dynamic() {
+    let bmp =
+        DynamicBmp::<Rgb888>::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")).unwrap();
+    draw_image(bmp).assert_pattern(&[
+        "WWKKWWKK", //
+        "WWKKWWKK", //
+        "KKWWKKWW", //
+        "KKWWKKWW", //
+        "WWKKWWKK", //
+        "WWKKWWKK", //
+        "KKWWKKWW", //
+        "KKWWKKWW", //
+    ]);
+}
+
+#[test]
+fn detect_color_type() {
+    let cases: [(&[u8], ColorType); 8] = [
+        (include_bytes!("./colors_rgb555.bmp"), ColorType::Rgb555),
+        (include_bytes!("./colors_rgb565.bmp"), ColorType::Rgb565),
+        (
+            include_bytes!("./colors_rgb888_24bit.bmp"),
+            ColorType::Rgb888,
+        ),
+        (
+            include_bytes!("./colors_rgb888_32bit.bmp"),
+            ColorType::Rgb888,
+        ),
+        (include_bytes!("./colors_grey8.bmp"), ColorType::Gray8),
+        (
+            include_bytes!("./chessboard-8px-1bit.bmp"),
+            ColorType::Rgb888,
+        ),
+        (include_bytes!("./logo-rgb555.bmp"), ColorType::Rgb555),
+        (
+            include_bytes!("./colors_4bpp_indexed.bmp"),
+            ColorType::Rgb888,
+        ),
+    ];
+
+    for (data, expected) in cases {
+        let raw = RawBmp::from_slice(data).unwrap();
+        assert_eq!(raw.detect_color_type(), Ok(expected));
+
+        let bmp = DynamicBmp::<Rgb888>::from_slice(data).unwrap();
+        assert_eq!(bmp.color_type(), expected);
+    }
+}
+
+#[test]
+fn sub_image() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let sub_image = bmp.sub_image(&Rectangle::new(Point::new(1, 0), Size::new(2, 2)));
+
+    draw_image(sub_image).assert_pattern(&[
+        "RG", //
+        "MC", //
+    ]);
+}
+
+#[test]
+fn sub_image_dynamic() {
+    let bmp =
+        DynamicBmp::<Rgb565>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let sub_image = bmp.sub_image(&Rectangle::new(Point::new(2, 1), Size::new(2, 1)));
+
+    draw_image(sub_image).assert_pattern(&[
+        "CW", //
+    ]);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, Bpp, CompressionMethod, DynamicBmp, EmbeddedStream, ParseError, RawBmp};
+
+const EMBEDDED_PNG: &[u8] = include_bytes!("./embedded-png.bmp");
+
+/// Offset of the image data in `embedded-png.bmp`.
+const IMAGE_DATA_START: usize = 54;
+
+/// Offset of the compression field in a BMP file.
+const COMPRESSION_OFFSET: usize = 30;
+
+#[test]
+fn png() {
+    let bmp = RawBmp::from_slice(EMBEDDED_PNG).unwrap();
+
+    assert_eq!(bmp.size(), Size::new(1, 1));
+    assert_eq!(bmp.color_bpp(), Bpp::Implied);
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Png);
+    assert_eq!(
+        bmp.embedded_stream(),
+        Some(EmbeddedStream::Png(&EMBEDDED_PNG[IMAGE_DATA_START..]))
+    );
+}
+
+#[test]
+fn jpeg() {
+    let mut file = EMBEDDED_PNG.to_vec();
+    file[COMPRESSION_OFFSET] = 4;
+
+    let bmp = RawBmp::from_slice(&file).unwrap();
+
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Jpeg);
+    assert_eq!(
+        bmp.embedded_stream(),
+        Some(EmbeddedStream::Jpeg(&file[IMAGE_DATA_START..]))
+    );
+}
+
+#[test]
+fn stream_length() {
+    // Trailing data after the stream isn't included in the returned slice.
+    let mut file = EMBEDDED_PNG.to_vec();
+    file.extend_from_slice(&[0; 8]);
+
+    let bmp = RawBmp::from_slice(&file).unwrap();
+    assert_eq!(
+        bmp.embedded_stream(),
+        Some(EmbeddedStream::Png(&EMBEDDED_PNG[IMAGE_DATA_START..]))
+    );
+
+    // Truncated streams are rejected.
+    assert_eq!(
+        RawBmp::from_slice(&EMBEDDED_PNG[..EMBEDDED_PNG.len() - 1]),
+        Err(ParseError::UnexpectedEndOfFile)
+    );
+}
+
+#[test]
+fn uncompressed_image() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    assert_eq!(bmp.embedded_stream(), None);
+}
+
+#[test]
+fn draw_embedded_stream() {
+    assert_eq!(
+        Bmp::<Rgb888>::from_slice(EMBEDDED_PNG),
+        Err(ParseError::UnsupportedEmbeddedStream)
+    );
+    assert_eq!(
+        DynamicBmp::<Rgb888>::from_slice(EMBEDDED_PNG),
+        Err(ParseError::UnsupportedEmbeddedStream)
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Gray8, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, Bpp, ColorType, DynamicBmp, RawBmp};
+
+const HDR_64BPP: &[u8] = include_bytes!("./hdr-64bpp.bmp");
+const GRAY16: &[u8] = include_bytes!("./gray16.bmp");
+
+#[test]
+fn bpp_64() {
+    let bmp = RawBmp::from_slice(HDR_64BPP).unwrap();
+    assert_eq!(bmp.color_bpp(), Bpp::Bits64);
+
+    // Raw values are tone mapped to RGB888. Channels outside of the 0.0 to 1.0 range are
+    // clamped and a linear value of 0.214 is mapped to the sRGB value 128.
+    let colors = bmp.pixels().map(|p| p.color).collect::<Vec<_>>();
+    assert_eq!(colors, [0x000000, 0xFF0000, 0x00FF80, 0xFFFFFF]);
+}
+
+#[test]
+fn draw_bpp_64() {
+    let expected = [
+        Rgb888::BLACK,
+        Rgb888::RED,
+        Rgb888::new(0, 255, 128),
+        Rgb888::WHITE,
+    ];
+
+    let bmp = Bmp::<Rgb888>::from_slice(HDR_64BPP).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(HDR_64BPP).unwrap();
+    assert_eq!(bmp.color_type(), ColorType::Rgb888);
+    let mut dynamic_display = MockDisplay::new();
+    Image::new(&bmp, Point::zero())
+        .draw(&mut dynamic_display)
+        .unwrap();
+
+    for (x, color) in expected.into_iter().enumerate() {
+        let p = Point::new(x as i32, 0);
+        assert_eq!(display.get_pixel(p), Some(color));
+        assert_eq!(dynamic_display.get_pixel(p), Some(color));
+        assert_eq!(bmp.pixel(p), Some(color));
+    }
+}
+
+#[test]
+fn gray16() {
+    let bmp = DynamicBmp::<Gray8>::from_slice(GRAY16).unwrap();
+    assert_eq!(bmp.color_type(), ColorType::Gray16);
+
+    let expected = [Gray8::new(0), Gray8::new(128), Gray8::new(255)];
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    for (x, color) in expected.into_iter().enumerate() {
+        let p = Point::new(x as i32, 0);
+        assert_eq!(display.get_pixel(p), Some(color));
+        assert_eq!(bmp.pixel(p), Some(color));
+    }
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(GRAY16).unwrap();
+    assert_eq!(bmp.pixel(Point::new(2, 0)), Some(Rgb888::WHITE));
+}
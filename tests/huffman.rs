@@ -0,0 +1,240 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, Bpp, CompressionMethod, DynamicBmp, HeaderType, ParseError, RawBmp, RawPixel};
+
+const HUFFMAN_1D: &[u8] = include_bytes!("./os2-huffman-1d.bmp");
+
+/// Offset of the width field in a BMP file.
+const WIDTH_OFFSET: usize = 18;
+
+/// Offset of the height field in a BMP file.
+const HEIGHT_OFFSET: usize = 22;
+
+/// Offset of the BPP field in a BMP file.
+const BPP_OFFSET: usize = 28;
+
+/// Offset of the image data length field in a BMP file.
+const IMAGE_DATA_LEN_OFFSET: usize = 34;
+
+const EXPECTED: &[&str] = &[
+    "WWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWW",
+    "WWWWWWWWWWKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKWWWWWWWWWW",
+    "KKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKK",
+    "WKWKWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWWW",
+];
+
+#[test]
+fn header() {
+    let bmp = RawBmp::from_slice(HUFFMAN_1D).unwrap();
+
+    assert_eq!(bmp.header().header_type, HeaderType::Os2V2);
+    assert_eq!(
+        bmp.header().compression_method,
+        CompressionMethod::Huffman1d
+    );
+    assert_eq!(bmp.color_bpp(), Bpp::Bits1);
+    assert_eq!(bmp.size(), Size::new(64, 4));
+}
+
+/// Returns the expected raw pixels for the given rows.
+fn expected_pixels<'a>(rows: impl Iterator<Item = &'a &'a str>) -> Vec<RawPixel> {
+    rows.enumerate()
+        .flat_map(|(y, row)| {
+            row.chars().enumerate().map(move |(x, c)| {
+                RawPixel::new(Point::new(x as i32, y as i32), u32::from(c == 'K'))
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn raw_pixels() {
+    let bmp = RawBmp::from_slice(HUFFMAN_1D).unwrap();
+    let expected = expected_pixels(EXPECTED.iter());
+
+    assert_eq!(bmp.pixels().collect::<Vec<_>>(), expected);
+
+    for pixel in &expected {
+        assert_eq!(bmp.pixel(pixel.position), Some(pixel.color));
+    }
+
+    for n in [0, 1, 63, 64, 65, 100, 130, 255] {
+        assert_eq!(bmp.pixels().nth(n), Some(expected[n]), "{n}");
+    }
+
+    // Skipping backwards in the image data needs to restart the decoder.
+    let mut pixels = bmp.pixels();
+    assert_eq!(pixels.nth(130), Some(expected[130]));
+    assert_eq!(pixels.nth(70), Some(expected[201]));
+    assert_eq!(pixels.next(), Some(expected[202]));
+
+    let mut reversed = bmp.pixels().rev().collect::<Vec<_>>();
+    reversed.reverse();
+    assert_eq!(reversed, expected);
+}
+
+#[test]
+fn top_down() {
+    let mut file = HUFFMAN_1D.to_vec();
+    let height = i32::from_le_bytes(file[HEIGHT_OFFSET..HEIGHT_OFFSET + 4].try_into().unwrap());
+    file[HEIGHT_OFFSET..HEIGHT_OFFSET + 4].copy_from_slice(&(-height).to_le_bytes());
+
+    // The decoder is reused for the following rows of top-down images.
+    let bmp = RawBmp::from_slice(&file).unwrap();
+    assert_eq!(
+        bmp.pixels().collect::<Vec<_>>(),
+        expected_pixels(EXPECTED.iter().rev())
+    );
+}
+
+/// Creates a Huffman 1D compressed image with the given number of 8 px wide rows.
+///
+/// Each row starts with `y % 9` white pixels, followed by black pixels.
+fn tall_image(height: usize) -> Vec<u8> {
+    // Codes for white runs of 0 to 8 pixels as `(length, code)`.
+    const WHITE: [(u8, u16); 9] = [
+        (8, 0b00110101),
+        (6, 0b000111),
+        (4, 0b0111),
+        (4, 0b1000),
+        (4, 0b1011),
+        (4, 0b1100),
+        (4, 0b1110),
+        (4, 0b1111),
+        (5, 0b10011),
+    ];
+    // Codes for black runs of 1 to 8 pixels.
+    const BLACK: [(u8, u16); 8] = [
+        (3, 0b010),
+        (2, 0b11),
+        (2, 0b10),
+        (3, 0b011),
+        (4, 0b0011),
+        (4, 0b0010),
+        (5, 0b00011),
+        (6, 0b000101),
+    ];
+
+    let mut bits = Vec::new();
+    for y in 0..height {
+        // Rows which end with a white run don't contain a black run.
+        let white = y % 9;
+        let runs = if white == 8 {
+            &[WHITE[white]][..]
+        } else {
+            &[WHITE[white], BLACK[7 - white]][..]
+        };
+
+        for (length, code) in runs {
+            bits.extend((0..*length).rev().map(|bit| code & (1 << bit) != 0));
+        }
+    }
+
+    let image_data = bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, bit)| acc | (u8::from(*bit) << (7 - i)))
+        })
+        .collect::<Vec<_>>();
+
+    let bmp = RawBmp::from_slice(HUFFMAN_1D).unwrap();
+    let mut file = HUFFMAN_1D[..bmp.header().image_data_start].to_vec();
+    file[WIDTH_OFFSET..WIDTH_OFFSET + 4].copy_from_slice(&8u32.to_le_bytes());
+    file[HEIGHT_OFFSET..HEIGHT_OFFSET + 4].copy_from_slice(&(height as u32).to_le_bytes());
+    file[IMAGE_DATA_LEN_OFFSET..IMAGE_DATA_LEN_OFFSET + 4]
+        .copy_from_slice(&(image_data.len() as u32).to_le_bytes());
+    file.extend_from_slice(&image_data);
+
+    file
+}
+
+#[test]
+fn bottom_up_rows() {
+    let height = 50;
+    let file = tall_image(height);
+    let bmp = RawBmp::from_slice(&file).unwrap();
+
+    // The rows are iterated in reverse order of the image data.
+    let expected = (0..height)
+        .flat_map(|y| {
+            let white = (height - 1 - y) % 9;
+            (0..8)
+                .map(move |x| RawPixel::new(Point::new(x, y as i32), u32::from(x >= white as i32)))
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(bmp.pixels().collect::<Vec<_>>(), expected);
+
+    for n in [0, 7, 8, 9, 200, 399] {
+        assert_eq!(bmp.pixels().nth(n), Some(expected[n]), "{n}");
+        assert_eq!(
+            bmp.pixels().nth_back(n),
+            expected.iter().rev().nth(n).copied(),
+            "{n}"
+        );
+    }
+
+    assert!(bmp.pixels().rev().eq(expected.iter().rev().copied()));
+}
+
+#[test]
+fn draw() {
+    let bmp = Bmp::<Rgb888>::from_slice(HUFFMAN_1D).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+
+    let bmp = Bmp::<Rgb888>::from_slice(HUFFMAN_1D)
+        .unwrap()
+        .with_transparent_color(Rgb888::WHITE);
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    let expected = EXPECTED
+        .iter()
+        .map(|row| row.replace('W', " "))
+        .collect::<Vec<_>>();
+    display.assert_pattern(&expected.iter().map(String::as_str).collect::<Vec<_>>());
+
+    let bmp = Bmp::<BinaryColor>::from_slice(HUFFMAN_1D).unwrap();
+    assert_eq!(bmp.pixel(Point::new(0, 2)), Some(BinaryColor::Off));
+    assert_eq!(bmp.pixel(Point::new(0, 0)), Some(BinaryColor::On));
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(HUFFMAN_1D).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(EXPECTED);
+}
+
+#[test]
+fn invalid_data() {
+    // Invalid data is decoded as white pixels.
+    let bmp = RawBmp::from_slice(HUFFMAN_1D).unwrap();
+    let image_data_start = bmp.header().image_data_start;
+
+    let mut file = HUFFMAN_1D.to_vec();
+    file.truncate(image_data_start + 2);
+
+    let bmp = RawBmp::from_slice(&file).unwrap();
+    assert!(bmp.pixels().all(|pixel| pixel.color == 0));
+}
+
+#[test]
+fn unsupported_bpp() {
+    let mut file = HUFFMAN_1D.to_vec();
+    file[BPP_OFFSET] = 8;
+
+    assert_eq!(
+        RawBmp::from_slice(&file),
+        Err(ParseError::UnsupportedBpp(8))
+    );
+}
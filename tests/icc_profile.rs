@@ -0,0 +1,97 @@
+mod common;
+
+use common::tinybmp;
+
+use tinybmp::{ColorSpace, IccProfile, ParseError, RawBmp, RenderingIntent};
+
+/// Creates a 1x1 px 24 BPP image with a V5 DIB header.
+///
+/// The profile data is appended after the image data.
+fn v5_image(color_space_type: &[u8; 4], profile: &[u8], profile_size: u32) -> Vec<u8> {
+    const FILE_HEADER_SIZE: u32 = 14;
+    const DIB_HEADER_SIZE: u32 = 124;
+    const IMAGE_DATA_SIZE: u32 = 4;
+
+    let image_data_start = FILE_HEADER_SIZE + DIB_HEADER_SIZE;
+    let file_size = image_data_start + IMAGE_DATA_SIZE + profile.len() as u32;
+
+    let mut data = Vec::new();
+
+    // File header
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&file_size.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&image_data_start.to_le_bytes());
+
+    // DIB header
+    data.extend_from_slice(&DIB_HEADER_SIZE.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes()); // width
+    data.extend_from_slice(&1i32.to_le_bytes()); // height
+    data.extend_from_slice(&1u16.to_le_bytes()); // color planes
+    data.extend_from_slice(&24u16.to_le_bytes()); // bpp
+    data.extend_from_slice(&0u32.to_le_bytes()); // compression
+    data.extend_from_slice(&IMAGE_DATA_SIZE.to_le_bytes());
+    data.extend_from_slice(&[0; 16]); // resolution and color counts
+    data.extend_from_slice(&[0; 16]); // channel masks
+    data.extend_from_slice(&u32::from_be_bytes(*color_space_type).to_le_bytes());
+    data.extend_from_slice(&[0; 36]); // endpoints
+    data.extend_from_slice(&[0; 12]); // gamma
+    data.extend_from_slice(&4u32.to_le_bytes()); // rendering intent
+    data.extend_from_slice(&(DIB_HEADER_SIZE + IMAGE_DATA_SIZE).to_le_bytes());
+    data.extend_from_slice(&profile_size.to_le_bytes());
+    data.extend_from_slice(&[0; 4]); // reserved
+
+    // Image data
+    data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x00]);
+
+    data.extend_from_slice(profile);
+
+    data
+}
+
+#[test]
+fn embedded_profile() {
+    let data = v5_image(b"MBED", b"profile data", 12);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    assert_eq!(bmp.header().color_space, Some(ColorSpace::EmbeddedProfile));
+    assert_eq!(
+        bmp.header().rendering_intent,
+        Some(RenderingIntent::Perceptual)
+    );
+    assert_eq!(
+        bmp.icc_profile(),
+        Some(IccProfile::Embedded(b"profile data"))
+    );
+}
+
+#[test]
+fn linked_profile() {
+    let data = v5_image(b"LINK", b"C:\\profile.icm\0", 15);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    assert_eq!(bmp.header().color_space, Some(ColorSpace::LinkedProfile));
+    assert_eq!(
+        bmp.icc_profile(),
+        Some(IccProfile::Linked(b"C:\\profile.icm"))
+    );
+}
+
+#[test]
+fn no_profile() {
+    let data = v5_image(b"sRGB", &[], 0);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    assert_eq!(bmp.header().color_space, Some(ColorSpace::Srgb));
+    assert_eq!(bmp.icc_profile(), None);
+}
+
+#[test]
+fn profile_out_of_bounds() {
+    let data = v5_image(b"MBED", b"profile data", 13);
+
+    assert_eq!(
+        RawBmp::from_slice(&data),
+        Err(ParseError::UnexpectedEndOfFile)
+    );
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Ico, IcoType, ParseError};
+
+const ICON: &[u8] = include_bytes!("./icon.ico");
+const CURSOR: &[u8] = include_bytes!("./cursor.cur");
+
+fn draw_image<T>(image: &T) -> MockDisplay<T::Color>
+where
+    T: ImageDrawable,
+{
+    let mut display = MockDisplay::new();
+    Image::new(image, Point::zero()).draw(&mut display).unwrap();
+
+    display
+}
+
+#[test]
+fn entries() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    assert_eq!(ico.ico_type(), IcoType::Icon);
+    assert_eq!(ico.len(), 3);
+
+    let entries = ico
+        .entries()
+        .map(|entry| (entry.size(), entry.bpp(), entry.is_png()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        entries,
+        [
+            (Size::new(8, 8), 1, false),
+            (Size::new(4, 4), 24, false),
+            (Size::new(32, 32), 32, true),
+        ]
+    );
+}
+
+#[test]
+fn best_match() {
+    let ico = Ico::from_slice(ICON).unwrap();
+
+    let best_match_size = |size| ico.best_match(size).unwrap().size();
+
+    assert_eq!(best_match_size(Size::new(4, 4)), Size::new(4, 4));
+    assert_eq!(best_match_size(Size::new(6, 6)), Size::new(8, 8));
+    assert_eq!(best_match_size(Size::new(2, 2)), Size::new(4, 4));
+    assert_eq!(best_match_size(Size::new(16, 16)), Size::new(32, 32));
+    assert_eq!(best_match_size(Size::new(64, 64)), Size::new(32, 32));
+}
+
+#[test]
+fn draw_24bpp_entry() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let image = ico.entry(1).unwrap().image::<Rgb888>().unwrap();
+
+    assert_eq!(image.size(), Size::new(4, 4));
+    draw_image(&image).assert_pattern(&[
+        " GB ", //
+        "KRGB", //
+        "WKRG", //
+        "BWKR", //
+    ]);
+}
+
+#[test]
+fn draw_32bpp_alpha_entry() {
+    let ico = Ico::from_slice(include_bytes!("./icon-32bpp-alpha.ico")).unwrap();
+
+    // The AND mask of this entry is empty and the alpha channel is used instead.
+    let image = ico.entry(0).unwrap().image::<Rgb888>().unwrap();
+    draw_image(&image).assert_pattern(&[
+        "R B ", //
+        "K  Y", //
+    ]);
+
+    // The alpha values of this entry are all zero and the AND mask is used instead.
+    let image = ico.entry(1).unwrap().dynamic_image::<Rgb888>().unwrap();
+    draw_image(&image).assert_pattern(&[
+        "R BW", //
+        "K  Y", //
+    ]);
+}
+
+#[test]
+fn draw_1bpp_entry() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let image = ico.entry(0).unwrap().image::<BinaryColor>().unwrap();
+
+    draw_image(&image).assert_pattern(&[
+        "  ..##..", //
+        "  ..##..", //
+        "..##..##", //
+        "..##..##", //
+        "##..##..", //
+        "##..##..", //
+        "..##..##", //
+        "..##..##", //
+    ]);
+}
+
+#[test]
+fn draw_dynamic_entry() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let image = ico.entry(1).unwrap().dynamic_image::<Rgb888>().unwrap();
+
+    draw_image(&image).assert_pattern(&[
+        " GB ", //
+        "KRGB", //
+        "WKRG", //
+        "BWKR", //
+    ]);
+}
+
+#[test]
+fn png_entry() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let entry = ico.entry(2).unwrap();
+
+    assert!(entry.data().starts_with(b"\x89PNG"));
+    assert_eq!(
+        entry.image::<Rgb888>().unwrap_err(),
+        ParseError::UnsupportedIcoEntry
+    );
+}
+
+#[test]
+fn cursor() {
+    let cur = Ico::from_slice(CURSOR).unwrap();
+    assert_eq!(cur.ico_type(), IcoType::Cursor);
+
+    let entry = cur.entry(0).unwrap();
+    assert_eq!(entry.hotspot(), Some(Point::new(1, 2)));
+    assert_eq!(entry.bpp(), 24);
+
+    draw_image(&entry.image::<Rgb888>().unwrap()).assert_pattern(&[
+        " GB ", //
+        "KRGB", //
+        "WKRG", //
+        "BWKR", //
+    ]);
+}
+
+#[test]
+fn invalid_files() {
+    assert_eq!(
+        Ico::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")),
+        Err(ParseError::InvalidFileSignature)
+    );
+    assert_eq!(
+        Ico::from_slice(&ICON[..ICON.len() - 1]),
+        Err(ParseError::UnexpectedEndOfFile)
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use image::{DynamicImage, Rgb, RgbImage, Rgba};
+use tinybmp::{encode_rgb_image, Bmp, DynamicBmp, EncodeError, RawBmp};
+
+#[test]
+fn raw_bmp_to_rgba_image() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let image = bmp.to_rgba_image().unwrap();
+
+    assert_eq!(image.dimensions(), (4, 2));
+    assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+    assert_eq!(image.get_pixel(1, 0), &Rgba([255, 0, 0, 255]));
+    assert_eq!(image.get_pixel(2, 0), &Rgba([0, 255, 0, 255]));
+    assert_eq!(image.get_pixel(3, 0), &Rgba([255, 255, 0, 255]));
+    assert_eq!(image.get_pixel(0, 1), &Rgba([0, 0, 255, 255]));
+    assert_eq!(image.get_pixel(3, 1), &Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn dynamic_bmp_to_dynamic_image() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
+    let image = bmp.to_dynamic_image();
+
+    let image = match image {
+        DynamicImage::ImageLuma8(image) => image,
+        _ => panic!("expected a grayscale image"),
+    };
+    assert_eq!(image.dimensions(), bmp.size().into());
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap();
+    assert!(matches!(bmp.to_dynamic_image(), DynamicImage::ImageRgb8(_)));
+}
+
+#[test]
+fn indexed_to_dynamic_image() {
+    // Indexed color images must not be converted into grayscale images.
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_8bpp_indexed.bmp")).unwrap();
+    let image = match bmp.to_dynamic_image().unwrap() {
+        DynamicImage::ImageRgb8(image) => image,
+        _ => panic!("expected an RGB image"),
+    };
+
+    let expected =
+        Bmp::<Rgb888>::from_slice(include_bytes!("./colors_8bpp_non_indexed.bmp")).unwrap();
+    assert_eq!(image.dimensions(), expected.size().into());
+
+    for (x, y, Rgb([r, g, b])) in image.enumerate_pixels() {
+        assert_eq!(
+            expected.pixel(Point::new(x as i32, y as i32)),
+            Some(Rgb888::new(*r, *g, *b))
+        );
+    }
+}
+
+#[test]
+fn encode_rgb_image_round_trip() {
+    // Odd width to check that rows are padded to a multiple of 4 bytes.
+    let image = RgbImage::from_fn(3, 2, |x, y| Rgb([x as u8 * 100, y as u8 * 100, 50]));
+
+    let data = encode_rgb_image(&image).unwrap();
+    let bmp = Bmp::<Rgb888>::from_slice(&data).unwrap();
+
+    assert_eq!(bmp.size(), Size::new(3, 2));
+    for (x, y, Rgb([r, g, b])) in image.enumerate_pixels() {
+        assert_eq!(
+            bmp.pixel(Point::new(x as i32, y as i32)),
+            Some(Rgb888::new(*r, *g, *b))
+        );
+    }
+
+    assert_eq!(
+        RawBmp::from_slice(&data).unwrap().to_rgba_image().unwrap(),
+        DynamicImage::ImageRgb8(image).to_rgba8()
+    );
+}
+
+#[test]
+fn encode_rgb_image_too_large() {
+    // The width can't be stored in the signed width field of the header.
+    let image = RgbImage::new(1 << 31, 0);
+    assert_eq!(encode_rgb_image(&image), Err(EncodeError::ImageTooLarge));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::pixelcolor::Rgb888;
+use tinybmp::{Bmp, RawBmp, RawPixel};
+
+const FILES: [&[u8]; 9] = [
+    include_bytes!("./chessboard-8px-1bit.bmp"),
+    include_bytes!("./colors_4bpp_indexed.bmp"),
+    include_bytes!("./colors_rgb888_24bit.bmp"),
+    include_bytes!("./colors_rgb565.bmp"),
+    include_bytes!("./issue_8-image_top_down.bmp"),
+    include_bytes!("./logo-indexed-1bpp.bmp"),
+    include_bytes!("./os2-huffman-1d.bmp"),
+    include_bytes!("./rle4.bmp"),
+    include_bytes!("./rle8.bmp"),
+];
+
+/// Collects the pixels by only using `next`.
+fn raw_pixels(bmp: &RawBmp<'_>) -> Vec<RawPixel> {
+    let mut pixels = bmp.pixels();
+
+    std::iter::from_fn(|| pixels.next()).collect()
+}
+
+#[test]
+fn len() {
+    for file in FILES {
+        let bmp = RawBmp::from_slice(file).unwrap();
+        let expected = raw_pixels(&bmp);
+
+        let mut pixels = bmp.pixels();
+        assert_eq!(pixels.len(), expected.len());
+        assert_eq!(pixels.size_hint(), (expected.len(), Some(expected.len())));
+
+        pixels.next();
+        pixels.next_back();
+        assert_eq!(pixels.len(), expected.len() - 2);
+        assert_eq!(pixels.count(), expected.len() - 2);
+    }
+}
+
+#[test]
+fn rev() {
+    for file in FILES {
+        let bmp = RawBmp::from_slice(file).unwrap();
+        let expected = raw_pixels(&bmp);
+
+        assert!(bmp.pixels().rev().eq(expected.iter().rev().copied()));
+        assert_eq!(bmp.pixels().last(), expected.last().copied());
+    }
+}
+
+#[test]
+fn nth() {
+    for file in FILES {
+        let bmp = RawBmp::from_slice(file).unwrap();
+        let expected = raw_pixels(&bmp);
+        let width = bmp.size().width as usize;
+
+        for n in [0, 1, width - 1, width, width + 1, expected.len() - 1] {
+            let mut pixels = bmp.pixels();
+            assert_eq!(pixels.nth(n), expected.get(n).copied());
+            assert_eq!(pixels.next(), expected.get(n + 1).copied());
+            assert_eq!(pixels.len(), expected.len().saturating_sub(n + 2));
+
+            assert!(bmp.pixels().skip(n).eq(expected[n..].iter().copied()));
+            assert_eq!(
+                bmp.pixels().nth_back(n),
+                expected.iter().rev().nth(n).copied()
+            );
+        }
+
+        let mut pixels = bmp.pixels();
+        assert_eq!(pixels.nth(expected.len()), None);
+        assert_eq!(pixels.next(), None);
+        assert_eq!(pixels.next_back(), None);
+    }
+}
+
+#[test]
+fn double_ended() {
+    for file in FILES {
+        let bmp = RawBmp::from_slice(file).unwrap();
+        let expected = raw_pixels(&bmp);
+
+        // Alternate between both ends until the iterator meets in the middle.
+        let mut pixels = bmp.pixels();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(pixel) = pixels.next() {
+            front.push(pixel);
+
+            match pixels.next_back() {
+                Some(pixel) => back.push(pixel),
+                None => break,
+            }
+        }
+
+        front.extend(back.into_iter().rev());
+        assert_eq!(front, expected);
+    }
+}
+
+#[test]
+fn pixels() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let expected = bmp.pixels().collect::<Vec<_>>();
+
+    assert_eq!(bmp.pixels().len(), 8);
+    assert!(bmp.pixels().rev().eq(expected.iter().rev().copied()));
+    assert_eq!(bmp.pixels().nth(5), Some(expected[5]));
+    assert_eq!(bmp.pixels().nth_back(2), Some(expected[5]));
+    assert_eq!(bmp.pixels().skip(6).len(), 2);
+}
@@ -0,0 +1,262 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    pixelcolor::{BinaryColor, Rgb565, Rgb888},
+    prelude::*,
+};
+use tinybmp::{
+    BitmapArray, Bmp, DynamicBmp, GetPixel, Ico, Limits, ParseError, RawBmp, RawPixel, Rotation,
+};
+
+const CHESSBOARD_24BIT: &[u8] = include_bytes!("./chessboard-8px-24bit.bmp");
+const CHESSBOARD_1BIT: &[u8] = include_bytes!("./chessboard-8px-1bit.bmp");
+
+/// Offset of the image width field in a BMP file.
+const WIDTH_OFFSET: usize = 18;
+
+/// Offset of the image height field in a BMP file.
+const HEIGHT_OFFSET: usize = 22;
+
+/// Offset of the BPP field in a BMP file.
+const BPP_OFFSET: usize = 28;
+
+/// Offset of the colors used field in a BMP file.
+const COLORS_USED_OFFSET: usize = 46;
+
+fn set_u32(file: &mut [u8], offset: usize, value: u32) {
+    file[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn default_limits() {
+    assert_eq!(Limits::default(), Limits::UNLIMITED);
+
+    assert_eq!(
+        RawBmp::from_slice_with_limits(CHESSBOARD_24BIT, Limits::default()),
+        RawBmp::from_slice(CHESSBOARD_24BIT)
+    );
+}
+
+#[test]
+fn width_limit() {
+    let limits = Limits {
+        max_width: 7,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        RawBmp::from_slice_with_limits(CHESSBOARD_24BIT, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+    assert_eq!(
+        Bmp::<Rgb888>::from_slice_with_limits(CHESSBOARD_24BIT, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+    assert_eq!(
+        DynamicBmp::<Rgb565>::from_slice_with_limits(CHESSBOARD_24BIT, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+
+    let limits = Limits {
+        max_width: 8,
+        ..Limits::default()
+    };
+    assert!(Bmp::<Rgb888>::from_slice_with_limits(CHESSBOARD_24BIT, limits).is_ok());
+}
+
+#[test]
+fn height_limit() {
+    let limits = Limits {
+        max_height: 4,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        Bmp::<Rgb888>::from_slice_with_limits(CHESSBOARD_24BIT, limits),
+        Err(ParseError::HeightLimitExceeded(8))
+    );
+}
+
+#[test]
+fn palette_limit() {
+    let limits = Limits {
+        max_palette_entries: 1,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        RawBmp::from_slice_with_limits(CHESSBOARD_1BIT, limits),
+        Err(ParseError::PaletteLimitExceeded(2))
+    );
+
+    // Images without a color table aren't affected by the palette limit.
+    let limits = Limits {
+        max_palette_entries: 0,
+        ..Limits::default()
+    };
+    assert!(RawBmp::from_slice_with_limits(CHESSBOARD_24BIT, limits).is_ok());
+}
+
+#[test]
+fn data_length_limit() {
+    // Each row contains 8 * 3 = 24 bytes.
+    let limits = Limits {
+        max_data_len: 8 * 24 - 1,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        RawBmp::from_slice_with_limits(CHESSBOARD_24BIT, limits),
+        Err(ParseError::DataLengthLimitExceeded(8 * 24))
+    );
+
+    let limits = Limits {
+        max_data_len: 8 * 24,
+        ..Limits::default()
+    };
+    assert!(RawBmp::from_slice_with_limits(CHESSBOARD_24BIT, limits).is_ok());
+}
+
+#[test]
+fn image_too_large() {
+    let mut file = CHESSBOARD_24BIT.to_vec();
+    set_u32(&mut file, WIDTH_OFFSET, u32::MAX);
+    set_u32(&mut file, HEIGHT_OFFSET, i32::MIN as u32);
+    file[BPP_OFFSET] = 64;
+
+    assert_eq!(RawBmp::from_slice(&file), Err(ParseError::ImageTooLarge));
+}
+
+#[test]
+fn minimum_height() {
+    // The absolute value of `i32::MIN` doesn't fit into an `i32`.
+    let mut file = CHESSBOARD_24BIT.to_vec();
+    set_u32(&mut file, WIDTH_OFFSET, 1);
+    set_u32(&mut file, HEIGHT_OFFSET, i32::MIN as u32);
+
+    assert_eq!(RawBmp::from_slice(&file), Err(ParseError::ImageTooLarge));
+}
+
+#[test]
+fn maximum_dimensions() {
+    // Pixel coordinates need to fit into an `i32`.
+    let mut file = CHESSBOARD_1BIT.to_vec();
+    set_u32(&mut file, WIDTH_OFFSET, 1 << 31);
+    set_u32(&mut file, HEIGHT_OFFSET, 1);
+
+    assert_eq!(RawBmp::from_slice(&file), Err(ParseError::ImageTooLarge));
+
+    set_u32(&mut file, WIDTH_OFFSET, i32::MAX as u32);
+    set_u32(&mut file, HEIGHT_OFFSET, (-i32::MAX) as u32);
+
+    let bmp = RawBmp::from_slice(&file).unwrap();
+    assert_eq!(bmp.size(), Size::new(i32::MAX as u32, i32::MAX as u32));
+
+    // Pixels outside the image data are returned as `0`.
+    let p = Point::new(i32::MAX - 1, i32::MAX - 1);
+    assert_eq!(bmp.pixel(p), Some(0));
+    assert_eq!(bmp.pixels().last(), Some(RawPixel::new(p, 0)));
+
+    let bmp = Bmp::<BinaryColor>::from_slice(&file).unwrap();
+    for rotation in [Rotation::Deg90, Rotation::Deg180, Rotation::Deg270] {
+        let image = bmp.rotated(rotation);
+        assert_eq!(image.pixel(Point::zero()), Some(BinaryColor::Off));
+        assert_eq!(image.pixel(p), Some(BinaryColor::Off));
+    }
+}
+
+#[test]
+fn huge_color_table() {
+    let mut file = CHESSBOARD_1BIT.to_vec();
+    set_u32(&mut file, COLORS_USED_OFFSET, u32::MAX);
+
+    assert_eq!(
+        RawBmp::from_slice(&file),
+        Err(ParseError::UnexpectedEndOfFile)
+    );
+}
+
+#[test]
+fn dib_limits() {
+    // Skip the 14 byte BMP file header.
+    let dib = &CHESSBOARD_24BIT[14..];
+    let limits = Limits {
+        max_width: 7,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        RawBmp::from_dib_slice_with_limits(dib, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+    assert_eq!(
+        Bmp::<Rgb888>::from_dib_slice_with_limits(dib, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+    assert_eq!(
+        DynamicBmp::<Rgb565>::from_dib_slice_with_limits(dib, limits),
+        Err(ParseError::WidthLimitExceeded(8))
+    );
+
+    let limits = Limits {
+        max_width: 8,
+        ..Limits::default()
+    };
+    assert_eq!(
+        RawBmp::from_dib_slice_with_limits(dib, limits),
+        RawBmp::from_dib_slice(dib)
+    );
+}
+
+#[test]
+fn ico_limits() {
+    let limits = Limits {
+        max_height: 4,
+        ..Limits::default()
+    };
+    let ico = Ico::from_slice_with_limits(include_bytes!("./icon.ico"), limits).unwrap();
+
+    let entry = ico.entry(0).unwrap();
+    assert_eq!(entry.size(), Size::new(8, 8));
+    assert_eq!(entry.as_raw(), Err(ParseError::HeightLimitExceeded(8)));
+    assert_eq!(
+        entry.image::<Rgb888>().err(),
+        Some(ParseError::HeightLimitExceeded(8))
+    );
+    assert_eq!(
+        entry.dynamic_image::<Rgb888>().err(),
+        Some(ParseError::HeightLimitExceeded(8))
+    );
+
+    // The AND mask isn't included in the height.
+    let entry = ico.entry(1).unwrap();
+    assert_eq!(entry.size(), Size::new(4, 4));
+    assert!(entry.image::<Rgb888>().is_ok());
+}
+
+#[test]
+fn bitmap_array_limits() {
+    let file = include_bytes!("./bitmap-array.bmp");
+    let limits = Limits {
+        max_width: 4,
+        ..Limits::default()
+    };
+
+    let array = BitmapArray::from_slice_with_limits(file, limits).unwrap();
+    let mut bitmaps = array.bitmaps();
+    assert_eq!(bitmaps.next().unwrap().unwrap().size(), Size::new(4, 2));
+    assert_eq!(bitmaps.next(), Some(Err(ParseError::WidthLimitExceeded(8))));
+    assert_eq!(bitmaps.next(), None);
+
+    // The first bitmap is checked if a bitmap array is loaded as a single image.
+    let limits = Limits {
+        max_width: 3,
+        ..Limits::default()
+    };
+    assert_eq!(
+        RawBmp::from_slice_with_limits(file, limits),
+        Err(ParseError::WidthLimitExceeded(4))
+    );
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Gray8, Rgb565, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, GetPixel, PaletteFn, RawBmp};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+const CHESSBOARD: &[u8] = include_bytes!("./chessboard-8px-1bit.bmp");
+
+#[test]
+fn slice_palette() {
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+    let palette = [Rgb565::RED, Rgb565::GREEN];
+
+    draw_image(bmp.with_palette(&palette[..]).unwrap()).assert_pattern(&[
+        "GGRRGGRR", //
+        "GGRRGGRR", //
+        "RRGGRRGG", //
+        "RRGGRRGG", //
+        "GGRRGGRR", //
+        "GGRRGGRR", //
+        "RRGGRRGG", //
+        "RRGGRRGG", //
+    ]);
+}
+
+#[test]
+fn closure_palette() {
+    let bmp = RawBmp::from_slice(CHESSBOARD).unwrap();
+    let palette = PaletteFn(|index| {
+        if index == 0 {
+            Rgb888::BLUE
+        } else {
+            Rgb888::YELLOW
+        }
+    });
+    let image = bmp.with_palette(palette).unwrap();
+
+    assert_eq!(image.pixel(Point::new(0, 0)), Some(Rgb888::YELLOW));
+    assert_eq!(image.pixel(Point::new(2, 0)), Some(Rgb888::BLUE));
+
+    draw_image(image).assert_pattern(&[
+        "YYBBYYBB", //
+        "YYBBYYBB", //
+        "BBYYBBYY", //
+        "BBYYBBYY", //
+        "YYBBYYBB", //
+        "YYBBYYBB", //
+        "BBYYBBYY", //
+        "BBYYBBYY", //
+    ]);
+}
+
+#[test]
+fn missing_palette_entries_are_transparent() {
+    let bmp = RawBmp::from_slice(CHESSBOARD).unwrap();
+    let image = bmp.with_palette([Rgb888::RED]).unwrap();
+
+    draw_image(image).assert_pattern(&[
+        "  RR  RR", //
+        "  RR  RR", //
+        "RR  RR  ", //
+        "RR  RR  ", //
+        "  RR  RR", //
+        "  RR  RR", //
+        "RR  RR  ", //
+        "RR  RR  ", //
+    ]);
+
+    assert_eq!(image.pixels().count(), 32);
+}
+
+#[test]
+fn palette_pixels() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_8bpp_indexed.bmp")).unwrap();
+    let image = bmp
+        .with_palette(PaletteFn(|index| Gray8::new(index as u8)))
+        .unwrap();
+
+    let pixels: Vec<_> = image.pixels().collect();
+    let expected: Vec<_> = bmp
+        .pixels()
+        .map(|pixel| Pixel(pixel.position, Gray8::new(pixel.color as u8)))
+        .collect();
+
+    assert_eq!(pixels.len(), 4 * 6);
+    assert_eq!(pixels, expected);
+}
+
+#[test]
+fn non_indexed_image() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap();
+
+    assert!(bmp.with_palette([Rgb888::RED]).is_none());
+}
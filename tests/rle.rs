@@ -0,0 +1,172 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{image::Image, mock_display::MockDisplay, pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, Bpp, CompressionMethod, DynamicBmp, ParseError, RawBmp, RawPixel};
+
+const RLE8: &[u8] = include_bytes!("./rle8.bmp");
+const RLE4: &[u8] = include_bytes!("./rle4.bmp");
+
+/// Offset of the BPP field in a BMP file.
+const BPP_OFFSET: usize = 28;
+
+/// Colors in the order of the color table entries.
+const COLORS: &str = "KRGBWYM";
+
+// The images contain encoded runs, absolute runs, deltas and pixels past the end of a row.
+const EXPECTED_RLE8: &[&str] = &[
+    "KKKKKMMM", //
+    "YYKKKKKK", //
+    "KKWWWWRR", //
+    "RRRGBWGB", //
+];
+
+const EXPECTED_RLE4: &[&str] = &[
+    "KKKKKMMM", //
+    "YYKKKKKK", //
+    "KKWWWWRY", //
+    "RGRBWGBW", //
+];
+
+#[test]
+fn header() {
+    let bmp = RawBmp::from_slice(RLE8).unwrap();
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Rle8);
+    assert_eq!(bmp.color_bpp(), Bpp::Bits8);
+    assert_eq!(bmp.size(), Size::new(8, 4));
+
+    let bmp = RawBmp::from_slice(RLE4).unwrap();
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Rle4);
+    assert_eq!(bmp.color_bpp(), Bpp::Bits4);
+    assert_eq!(bmp.size(), Size::new(8, 4));
+}
+
+/// Returns the expected raw pixels for the given pattern.
+fn expected_pixels(pattern: &[&str]) -> Vec<RawPixel> {
+    pattern
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars().enumerate().map(move |(x, c)| {
+                let index = COLORS.find(c).unwrap() as u32;
+                RawPixel::new(Point::new(x as i32, y as i32), index)
+            })
+        })
+        .collect()
+}
+
+fn assert_raw_pixels(file: &[u8], pattern: &[&str]) {
+    let bmp = RawBmp::from_slice(file).unwrap();
+    let expected = expected_pixels(pattern);
+
+    assert_eq!(bmp.pixels().collect::<Vec<_>>(), expected);
+
+    let mut reversed = bmp.pixels().rev().collect::<Vec<_>>();
+    reversed.reverse();
+    assert_eq!(reversed, expected);
+
+    for pixel in &expected {
+        assert_eq!(bmp.pixel(pixel.position), Some(pixel.color));
+    }
+
+    for n in [0, 1, 7, 8, 9, 20, 31] {
+        assert_eq!(bmp.pixels().nth(n), Some(expected[n]), "{n}");
+    }
+}
+
+#[test]
+fn raw_pixels() {
+    assert_raw_pixels(RLE8, EXPECTED_RLE8);
+    assert_raw_pixels(RLE4, EXPECTED_RLE4);
+}
+
+fn assert_draw(file: &[u8], pattern: &[&str]) {
+    let bmp = Bmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(pattern);
+
+    let bmp = DynamicBmp::<Rgb888>::from_slice(file).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(pattern);
+
+    let bmp = Bmp::<Rgb888>::from_slice(file)
+        .unwrap()
+        .with_transparent_index(0);
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    let expected = pattern
+        .iter()
+        .map(|row| row.replace('K', " "))
+        .collect::<Vec<_>>();
+    display.assert_pattern(&expected.iter().map(String::as_str).collect::<Vec<_>>());
+
+    // The palette swaps red and blue.
+    let palette = [
+        Rgb888::BLACK,
+        Rgb888::BLUE,
+        Rgb888::GREEN,
+        Rgb888::RED,
+        Rgb888::WHITE,
+        Rgb888::YELLOW,
+        Rgb888::MAGENTA,
+    ];
+    let bmp = RawBmp::from_slice(file)
+        .unwrap()
+        .with_palette(palette)
+        .unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    let expected = pattern
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| match c {
+                    'R' => 'B',
+                    'B' => 'R',
+                    c => c,
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    display.assert_pattern(&expected.iter().map(String::as_str).collect::<Vec<_>>());
+}
+
+#[test]
+fn draw() {
+    assert_draw(RLE8, EXPECTED_RLE8);
+    assert_draw(RLE4, EXPECTED_RLE4);
+}
+
+#[test]
+fn truncated_data() {
+    // Missing pixels are decoded as color index 0.
+    let bmp = RawBmp::from_slice(RLE8).unwrap();
+    let image_data_start = bmp.header().image_data_start;
+
+    let mut file = RLE8.to_vec();
+    // Truncate the image data in the middle of the absolute run in the first row.
+    file.truncate(image_data_start + 7);
+
+    let bmp = RawBmp::from_slice(&file).unwrap();
+    let mut expected = expected_pixels(&["KKKKKKKK"; 4]);
+    for (x, color) in [1, 1, 1, 2, 3, 4].into_iter().enumerate() {
+        expected[24 + x].color = color;
+    }
+    assert_eq!(bmp.pixels().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn unsupported_bpp() {
+    for (file, bpp) in [(RLE8, 4), (RLE4, 8)] {
+        let mut file = file.to_vec();
+        file[BPP_OFFSET] = bpp;
+
+        assert_eq!(
+            RawBmp::from_slice(&file),
+            Err(ParseError::UnsupportedBpp(bpp.into()))
+        );
+    }
+}
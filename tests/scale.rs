@@ -0,0 +1,112 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, DynamicBmp, GetPixel, Rotation, Transformed};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+fn bmp() -> Bmp<'static, Rgb565> {
+    Bmp::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap()
+}
+
+#[test]
+fn scaled_1x() {
+    draw_image(bmp().scaled(1)).assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn scaled_2x() {
+    let image = bmp().scaled(2);
+    assert_eq!(image.size(), Size::new(8, 4));
+
+    draw_image(image).assert_pattern(&[
+        "KKRRGGYY", //
+        "KKRRGGYY", //
+        "BBMMCCWW", //
+        "BBMMCCWW", //
+    ]);
+}
+
+#[test]
+fn dynamic_scaled_3x() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap();
+
+    draw_image(bmp.scaled(3)).assert_pattern(&[
+        "KKKRRRGGGYYY", //
+        "KKKRRRGGGYYY", //
+        "KKKRRRGGGYYY", //
+        "BBBMMMCCCWWW", //
+        "BBBMMMCCCWWW", //
+        "BBBMMMCCCWWW", //
+    ]);
+}
+
+#[test]
+fn scaled_to_larger() {
+    draw_image(bmp().scaled_to(Size::new(6, 3))).assert_pattern(&[
+        "KKRGGY", //
+        "KKRGGY", //
+        "BBMCCW", //
+    ]);
+}
+
+#[test]
+fn scaled_to_smaller() {
+    draw_image(bmp().scaled_to(Size::new(2, 1))).assert_pattern(&["KG"]);
+}
+
+#[test]
+fn scaled_pixel() {
+    let image = bmp().scaled(2);
+
+    assert_eq!(image.pixel(Point::new(3, 1)), Some(Rgb565::RED));
+    assert_eq!(image.pixel(Point::new(7, 3)), Some(Rgb565::WHITE));
+    assert_eq!(image.pixel(Point::new(8, 0)), None);
+}
+
+#[test]
+fn scaled_sub_image() {
+    let image = bmp().scaled(2);
+    let sub_image = image.sub_image(&Rectangle::new(Point::new(1, 1), Size::new(3, 2)));
+
+    draw_image(sub_image).assert_pattern(&[
+        "KRR", //
+        "BMM", //
+    ]);
+}
+
+#[test]
+fn scaled_and_rotated() {
+    draw_image(Transformed::new(bmp().scaled(2)).rotated(Rotation::Deg90)).assert_pattern(&[
+        "BBKK", //
+        "BBKK", //
+        "MMRR", //
+        "MMRR", //
+        "CCGG", //
+        "CCGG", //
+        "WWYY", //
+        "WWYY", //
+    ]);
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use std::cell::Cell;
+
+use tinybmp::{Bmp, DynamicBmp, GetPixel, SolidRuns};
+
+const RUNS: &[u8] = include_bytes!("./runs-24bit.bmp");
+
+const EXPECTED: &[&str] = &[
+    "RRRRRRRR", //
+    "RGBRRRRK", //
+    "GGBBWWWW", //
+];
+
+/// Draw call which was recorded by `CallRecorder`.
+#[derive(Debug, PartialEq)]
+enum Call {
+    FillSolid(Rectangle),
+    /// Area and number of colors which were consumed by the target.
+    FillContiguous(Rectangle, usize),
+}
+
+/// Draw target which records the calls to `fill_solid` and `fill_contiguous`.
+struct CallRecorder {
+    calls: Vec<Call>,
+}
+
+impl DrawTarget for CallRecorder {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        unreachable!("only fill_solid and fill_contiguous should be used")
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let count = colors.into_iter().count();
+        self.calls.push(Call::FillContiguous(*area, count));
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, _color: Self::Color) -> Result<(), Self::Error> {
+        self.calls.push(Call::FillSolid(*area));
+        Ok(())
+    }
+}
+
+impl OriginDimensions for CallRecorder {
+    fn size(&self) -> Size {
+        Size::new(64, 64)
+    }
+}
+
+fn span(x: i32, y: i32, width: u32) -> Rectangle {
+    Rectangle::new(Point::new(x, y), Size::new(width, 1))
+}
+
+#[test]
+fn draw() {
+    let bmp = Bmp::<Rgb888>::from_slice(RUNS).unwrap();
+
+    for min_run_length in [0, 1, 2, 4, 100] {
+        let mut display = MockDisplay::new();
+        Image::new(&bmp.solid_runs(min_run_length), Point::zero())
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(EXPECTED);
+    }
+
+    let bmp = DynamicBmp::<Rgb565>::from_slice(RUNS).unwrap();
+    let mut display = MockDisplay::new();
+    Image::new(&bmp.solid_runs(4), Point::zero())
+        .draw(&mut display)
+        .unwrap();
+    display.assert_pattern(EXPECTED);
+}
+
+#[test]
+fn calls() {
+    let bmp = Bmp::<Rgb888>::from_slice(RUNS).unwrap();
+
+    let mut recorder = CallRecorder { calls: Vec::new() };
+    bmp.solid_runs(4).draw(&mut recorder).unwrap();
+
+    assert_eq!(
+        recorder.calls,
+        [
+            Call::FillSolid(span(0, 0, 8)),
+            Call::FillContiguous(span(0, 1, 8), 3),
+            Call::FillSolid(span(3, 1, 4)),
+            Call::FillContiguous(span(7, 1, 1), 1),
+            Call::FillContiguous(span(0, 2, 8), 4),
+            Call::FillSolid(span(4, 2, 4)),
+        ]
+    );
+}
+
+#[test]
+fn calls_threshold() {
+    let bmp = Bmp::<Rgb888>::from_slice(RUNS).unwrap();
+
+    // Only use `fill_solid` for complete rows.
+    let mut recorder = CallRecorder { calls: Vec::new() };
+    bmp.solid_runs(8).draw(&mut recorder).unwrap();
+
+    assert_eq!(
+        recorder.calls,
+        [
+            Call::FillSolid(span(0, 0, 8)),
+            Call::FillContiguous(span(0, 1, 8), 8),
+            Call::FillContiguous(span(0, 2, 8), 8),
+        ]
+    );
+
+    // Use `fill_solid` for every run.
+    let mut recorder = CallRecorder { calls: Vec::new() };
+    bmp.solid_runs(1).draw(&mut recorder).unwrap();
+
+    assert_eq!(recorder.calls.len(), 1 + 5 + 3);
+    assert!(recorder
+        .calls
+        .iter()
+        .all(|call| matches!(call, Call::FillSolid(_))));
+}
+
+#[test]
+fn transparency() {
+    let bmp = Bmp::<Rgb888>::from_slice(RUNS)
+        .unwrap()
+        .with_transparent_color(Rgb888::BLUE);
+
+    let mut recorder = CallRecorder { calls: Vec::new() };
+    bmp.solid_runs(4).draw(&mut recorder).unwrap();
+
+    assert_eq!(
+        recorder.calls,
+        [
+            Call::FillSolid(span(0, 0, 8)),
+            Call::FillContiguous(span(0, 1, 8), 2),
+            Call::FillSolid(span(3, 1, 4)),
+            Call::FillContiguous(span(7, 1, 1), 1),
+            Call::FillContiguous(span(0, 2, 8), 2),
+            Call::FillSolid(span(4, 2, 4)),
+        ]
+    );
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp.solid_runs(4), Point::zero())
+        .draw(&mut display)
+        .unwrap();
+    display.assert_pattern(&[
+        "RRRRRRRR", //
+        "RG RRRRK", //
+        "GG  WWWW", //
+    ]);
+}
+
+#[test]
+fn compressed() {
+    for file in [
+        include_bytes!("./rle8.bmp").as_slice(),
+        include_bytes!("./rle4.bmp"),
+        include_bytes!("./os2-huffman-1d.bmp"),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(file).unwrap();
+
+        let mut expected = MockDisplay::new();
+        Image::new(&bmp, Point::zero()).draw(&mut expected).unwrap();
+
+        for min_run_length in [1, 3] {
+            let mut display = MockDisplay::new();
+            Image::new(&bmp.solid_runs(min_run_length), Point::zero())
+                .draw(&mut display)
+                .unwrap();
+            display.assert_eq(&expected);
+        }
+    }
+}
+
+/// Image which counts the number of pixel reads.
+struct CountingImage<'a> {
+    bmp: Bmp<'a, Rgb888>,
+    reads: Cell<usize>,
+}
+
+impl OriginDimensions for CountingImage<'_> {
+    fn size(&self) -> Size {
+        self.bmp.size()
+    }
+}
+
+impl GetPixel for CountingImage<'_> {
+    type Color = Rgb888;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        self.reads.set(self.reads.get() + 1);
+        self.bmp.pixel(p)
+    }
+}
+
+#[test]
+fn pixels_are_read_once() {
+    for min_run_length in [0, 2, 4, 100] {
+        let image = SolidRuns::new(
+            CountingImage {
+                bmp: Bmp::from_slice(RUNS).unwrap(),
+                reads: Cell::new(0),
+            },
+            min_run_length,
+        );
+
+        let mut recorder = CallRecorder { calls: Vec::new() };
+        image.draw(&mut recorder).unwrap();
+        assert_eq!(image.as_inner().reads.get(), 8 * 3, "{min_run_length}");
+    }
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image, mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*, primitives::Rectangle,
+};
+use tinybmp::{Bmp, GetPixel, TileSet};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+fn bmp() -> Bmp<'static, Rgb565> {
+    Bmp::from_slice(include_bytes!("./chessboard-8px-color-16bit.bmp")).unwrap()
+}
+
+#[test]
+fn tile_count() {
+    let tile_set = TileSet::new(bmp(), Size::new(4, 4));
+    assert_eq!(tile_set.columns(), 2);
+    assert_eq!(tile_set.rows(), 2);
+    assert_eq!(tile_set.len(), 4);
+
+    let tile_set = TileSet::new(bmp(), Size::new(3, 8));
+    assert_eq!(tile_set.columns(), 2);
+    assert_eq!(tile_set.rows(), 1);
+
+    let tile_set = TileSet::new(bmp(), Size::new(9, 1));
+    assert!(tile_set.is_empty());
+
+    let tile_set = TileSet::new(bmp(), Size::zero());
+    assert!(tile_set.is_empty());
+    assert_eq!(tile_set.tile(0), None);
+}
+
+#[test]
+fn tiles() {
+    let tile_set = TileSet::new(bmp(), Size::new(4, 4));
+
+    draw_image(tile_set.tile(0).unwrap()).assert_pattern(&[
+        "WWKK", //
+        "WWKK", //
+        "KKRR", //
+        "KKRR", //
+    ]);
+
+    draw_image(tile_set.tile(1).unwrap()).assert_pattern(&[
+        "WWKK", //
+        "WWKK", //
+        "KKGG", //
+        "KKGG", //
+    ]);
+
+    draw_image(tile_set.tile(3).unwrap()).assert_pattern(&[
+        "BBKK", //
+        "BBKK", //
+        "KKWW", //
+        "KKWW", //
+    ]);
+
+    assert_eq!(tile_set.tile(4), None);
+    assert_eq!(tile_set.tile(3), tile_set.tile_at(1, 1));
+}
+
+#[test]
+fn margin_and_spacing() {
+    let tile_set = TileSet::new(bmp(), Size::new(2, 2))
+        .with_margin(1)
+        .with_spacing(1);
+    assert_eq!(tile_set.len(), 4);
+
+    let tile = tile_set.tile_at(0, 0).unwrap();
+    assert_eq!(
+        tile.area(),
+        Rectangle::new(Point::new(1, 1), Size::new(2, 2))
+    );
+    draw_image(tile).assert_pattern(&[
+        "WK", //
+        "KR", //
+    ]);
+
+    draw_image(tile_set.tile(1).unwrap()).assert_pattern(&[
+        "WW", //
+        "KK", //
+    ]);
+
+    draw_image(tile_set.tile_at(1, 1).unwrap()).assert_pattern(&[
+        "BB", //
+        "BB", //
+    ]);
+
+    assert_eq!(tile_set.tile_at(2, 0), None);
+}
+
+#[test]
+fn tile_pixel() {
+    let tile = TileSet::new(bmp(), Size::new(4, 4)).tile(1).unwrap();
+
+    assert_eq!(tile.size(), Size::new(4, 4));
+    assert_eq!(tile.pixel(Point::new(3, 3)), Some(Rgb565::GREEN));
+    assert_eq!(tile.pixel(Point::new(4, 0)), None);
+}
+
+#[test]
+fn large_spacing() {
+    let tile_set = TileSet::new(bmp(), Size::new(4, 4)).with_spacing(u32::MAX);
+    assert_eq!(tile_set.columns(), 1);
+    assert_eq!(tile_set.rows(), 1);
+    assert_eq!(tile_set.len(), 1);
+    assert_eq!(
+        tile_set.tile(0).unwrap().area(),
+        Rectangle::new(Point::zero(), Size::new(4, 4))
+    );
+    assert!(tile_set.tile(1).is_none());
+
+    let tile_set = TileSet::new(bmp(), Size::new(4, 4))
+        .with_margin(u32::MAX)
+        .with_spacing(u32::MAX);
+    assert!(tile_set.is_empty());
+    assert!(tile_set.tile(0).is_none());
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, DynamicBmp, GetPixel, Rotation};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+fn bmp() -> Bmp<'static, Rgb565> {
+    Bmp::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap()
+}
+
+#[test]
+fn rotated_0() {
+    draw_image(bmp().rotated(Rotation::Deg0)).assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn rotated_90() {
+    let image = bmp().rotated(Rotation::Deg90);
+    assert_eq!(image.size(), Size::new(2, 4));
+
+    draw_image(image).assert_pattern(&[
+        "BK", //
+        "MR", //
+        "CG", //
+        "WY", //
+    ]);
+}
+
+#[test]
+fn rotated_180() {
+    draw_image(bmp().rotated(Rotation::Deg180)).assert_pattern(&[
+        "WCMB", //
+        "YGRK", //
+    ]);
+}
+
+#[test]
+fn rotated_270() {
+    let image = bmp().rotated(Rotation::Deg270);
+    assert_eq!(image.size(), Size::new(2, 4));
+
+    draw_image(image).assert_pattern(&[
+        "YW", //
+        "GC", //
+        "RM", //
+        "KB", //
+    ]);
+}
+
+#[test]
+fn flipped_horizontal() {
+    draw_image(bmp().flipped_horizontal()).assert_pattern(&[
+        "YGRK", //
+        "WCMB", //
+    ]);
+}
+
+#[test]
+fn flipped_vertical() {
+    draw_image(bmp().flipped_vertical()).assert_pattern(&[
+        "BMCW", //
+        "KRGY", //
+    ]);
+}
+
+#[test]
+fn chained_transforms() {
+    draw_image(bmp().rotated(Rotation::Deg90).flipped_horizontal()).assert_pattern(&[
+        "KB", //
+        "RM", //
+        "GC", //
+        "YW", //
+    ]);
+
+    draw_image(
+        bmp()
+            .flipped_horizontal()
+            .flipped_vertical()
+            .rotated(Rotation::Deg180),
+    )
+    .assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn transformed_pixel() {
+    let image = bmp().rotated(Rotation::Deg90);
+
+    assert_eq!(image.pixel(Point::new(0, 0)), Some(Rgb565::BLUE));
+    assert_eq!(image.pixel(Point::new(1, 3)), Some(Rgb565::YELLOW));
+    assert_eq!(image.pixel(Point::new(2, 0)), None);
+    assert_eq!(image.pixel(Point::new(0, -1)), None);
+}
+
+#[test]
+fn transformed_sub_image() {
+    let image = bmp().rotated(Rotation::Deg180);
+    let sub_image = image.sub_image(&Rectangle::new(Point::new(1, 0), Size::new(2, 2)));
+
+    draw_image(sub_image).assert_pattern(&[
+        "CM", //
+        "GR", //
+    ]);
+}
+
+#[test]
+fn dynamic_rotated_90() {
+    let bmp = DynamicBmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap();
+
+    draw_image(bmp.rotated(Rotation::Deg90)).assert_pattern(&[
+        "BK", //
+        "MR", //
+        "CG", //
+        "WY", //
+    ]);
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use common::{embedded_graphics, tinybmp};
+
+use embedded_graphics::{
+    image::Image,
+    mock_display::MockDisplay,
+    pixelcolor::{BinaryColor, Rgb565},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, Rotation};
+
+fn draw_image<C, T>(image_drawable: T) -> MockDisplay<C>
+where
+    C: PixelColor,
+    T: ImageDrawable<Color = C>,
+{
+    let image = Image::new(&image_drawable, Point::zero());
+
+    let mut display = MockDisplay::new();
+    image.draw(&mut display).unwrap();
+
+    display
+}
+
+fn bmp() -> Bmp<'static, Rgb565> {
+    Bmp::from_slice(include_bytes!("./colors_rgb565.bmp")).unwrap()
+}
+
+/// Draw target which records the spans of pixels which are drawn by `fill_contiguous`.
+struct SpanRecorder {
+    spans: Vec<Rectangle>,
+
+    /// Consume the colors passed to `fill_contiguous`.
+    consume_colors: bool,
+}
+
+impl SpanRecorder {
+    fn new(consume_colors: bool) -> Self {
+        Self {
+            spans: Vec::new(),
+            consume_colors,
+        }
+    }
+}
+
+impl DrawTarget for SpanRecorder {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        unreachable!("only fill_contiguous should be used")
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let width = if self.consume_colors {
+            colors.into_iter().count() as u32
+        } else {
+            0
+        };
+
+        self.spans
+            .push(Rectangle::new(area.top_left, Size::new(width, 1)));
+        Ok(())
+    }
+}
+
+impl OriginDimensions for SpanRecorder {
+    fn size(&self) -> Size {
+        Size::new(64, 64)
+    }
+}
+
+#[test]
+fn transparent_color() {
+    let image = bmp().with_transparent_color(Rgb565::RED);
+
+    assert_eq!(image.pixel(Point::new(1, 0)), None);
+    assert_eq!(image.pixel(Point::new(2, 0)), Some(Rgb565::GREEN));
+
+    draw_image(image).assert_pattern(&[
+        "K GY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn transparent_color_spans() {
+    let image = bmp().with_transparent_color(Rgb565::RED);
+
+    let mut recorder = SpanRecorder::new(true);
+    image.draw(&mut recorder).unwrap();
+
+    assert_eq!(
+        recorder.spans,
+        [
+            Rectangle::new(Point::new(0, 0), Size::new(1, 1)),
+            Rectangle::new(Point::new(2, 0), Size::new(2, 1)),
+            Rectangle::new(Point::new(0, 1), Size::new(4, 1)),
+        ]
+    );
+}
+
+#[test]
+fn unconsumed_spans() {
+    // Rows are skipped if the target doesn't consume any colors.
+    let image = bmp().with_transparent_color(Rgb565::RED);
+
+    let mut recorder = SpanRecorder::new(false);
+    image.draw(&mut recorder).unwrap();
+
+    assert_eq!(
+        recorder.spans,
+        [
+            Rectangle::new(Point::new(0, 0), Size::new(0, 1)),
+            Rectangle::new(Point::new(0, 1), Size::new(0, 1)),
+        ]
+    );
+}
+
+#[test]
+fn transparent_raw_value() {
+    // Raw value of green in RGB565.
+    let image = bmp().with_transparent_index(0x07E0);
+
+    draw_image(image).assert_pattern(&[
+        "KR Y", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn transparent_index() {
+    let image = Bmp::<BinaryColor>::from_slice(include_bytes!("./chessboard-8px-1bit.bmp"))
+        .unwrap()
+        .with_transparent_index(0);
+
+    draw_image(image).assert_pattern(&[
+        "##  ##  ", //
+        "##  ##  ", //
+        "  ##  ##", //
+        "  ##  ##", //
+        "##  ##  ", //
+        "##  ##  ", //
+        "  ##  ##", //
+        "  ##  ##", //
+    ]);
+}
+
+#[test]
+fn last_color_key_is_used() {
+    let image = bmp()
+        .with_transparent_index(0x07E0)
+        .with_transparent_color(Rgb565::BLUE);
+
+    draw_image(image).assert_pattern(&[
+        "KRGY", //
+        " MCW", //
+    ]);
+}
+
+#[test]
+fn transparent_rotated() {
+    let image = bmp()
+        .with_transparent_color(Rgb565::WHITE)
+        .rotated(Rotation::Deg90);
+
+    draw_image(image).assert_pattern(&[
+        "BK", //
+        "MR", //
+        "CG", //
+        " Y", //
+    ]);
+}
+
+#[test]
+fn transparent_scaled() {
+    let image = bmp().with_transparent_color(Rgb565::BLACK).scaled(2);
+
+    draw_image(image).assert_pattern(&[
+        "  RRGGYY", //
+        "  RRGGYY", //
+        "BBMMCCWW", //
+        "BBMMCCWW", //
+    ]);
+}
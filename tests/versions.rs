@@ -0,0 +1,62 @@
+//! Tests for using the embedded-graphics 0.7 and 0.8 APIs at the same time.
+#![cfg(all(feature = "embedded-graphics-0_7", feature = "embedded-graphics-0_8"))]
+
+const COLORS: &[u8] = include_bytes!("./colors_rgb565.bmp");
+
+const EXPECTED: &[&str] = &[
+    "KRGY", //
+    "BMCW", //
+];
+
+mod v0_7 {
+    use embedded_graphics::{
+        image::Image, mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*,
+    };
+    use tinybmp::{Bmp, ParseError};
+
+    use super::{COLORS, EXPECTED};
+
+    #[test]
+    fn draw() {
+        let bmp = Bmp::<Rgb565>::from_slice(COLORS).unwrap();
+
+        let mut display = MockDisplay::new();
+        Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+        display.assert_pattern(EXPECTED);
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            Bmp::<Rgb565>::from_slice(&COLORS[..10]),
+            Err(ParseError::UnexpectedEndOfFile)
+        );
+    }
+}
+
+mod v0_8 {
+    use embedded_graphics_0_8::{
+        image::Image, mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*,
+    };
+    use tinybmp::v0_8::Bmp;
+
+    use super::{COLORS, EXPECTED};
+
+    #[test]
+    fn draw() {
+        let bmp = Bmp::<Rgb565>::from_slice(COLORS).unwrap();
+
+        let mut display = MockDisplay::new();
+        Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+        display.assert_pattern(EXPECTED);
+    }
+
+    #[test]
+    fn parse_error() {
+        // The error type is shared by both APIs.
+        assert_eq!(
+            Bmp::<Rgb565>::from_slice(&COLORS[..10]),
+            Err(tinybmp::ParseError::UnexpectedEndOfFile)
+        );
+    }
+}